@@ -0,0 +1,121 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::hash::{ChainHasher, Hash, Hasher};
+use crate::{
+    hash::{Algorithm, HashMode},
+    metronome::DEFAULT_US_PER_REV,
+};
+
+use serde::{Deserialize, Serialize};
+
+// Default measurement window, long enough to get past turbo boost ramp-up.
+pub const DEFAULT_CALIBRATION_WINDOW: Duration = Duration::from_secs(2);
+
+// Hashes per timed batch, small enough to stop close to the end of the window.
+pub const DEFAULT_CALIBRATION_BATCH: u64 = 10_000;
+
+// Share of each rev left free for event mixins, channel sends and scheduling jitter.
+pub const DEFAULT_CALIBRATION_HEADROOM_PERCENT: u64 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalibrationConfig {
+    pub window: Duration,
+    pub batch: u64,
+    // Untimed batches run first so caches and clocks settle.
+    pub warmup_batches: u64,
+}
+
+impl Default for CalibrationConfig {
+    fn default() -> Self {
+        return Self {
+            window: DEFAULT_CALIBRATION_WINDOW,
+            batch: DEFAULT_CALIBRATION_BATCH,
+            warmup_batches: 1,
+        };
+    }
+}
+
+/// Sustained `extend_hash_chain` throughput measured on this machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Calibration {
+    pub algorithm: Algorithm,
+    // Calibrations saved before modes were recorded measured the legacy mode.
+    #[serde(default)]
+    pub mode: HashMode,
+    pub hashes: u64,
+    pub elapsed_us: u64,
+    pub hashes_per_second: u64,
+}
+
+impl Calibration {
+    /// Measures `algorithm` in the hash `mode` the chain will be generated in.
+    #[cfg(feature = "std")]
+    pub fn measure(algorithm: Algorithm, mode: HashMode, config: &CalibrationConfig) -> Self {
+        return Self::measure_with(&Hasher::with_mode(algorithm, mode), algorithm, mode, config);
+    }
+
+    /// Measures every supported algorithm in turn, in the given hash mode.
+    #[cfg(feature = "std")]
+    pub fn measure_all(mode: HashMode, config: &CalibrationConfig) -> Vec<Self> {
+        return Algorithm::ALL.into_iter().map(|algorithm| Self::measure(algorithm, mode, config)).collect();
+    }
+
+    #[cfg(feature = "std")]
+    pub fn measure_with<H: ChainHasher>(hasher: &H, algorithm: Algorithm, mode: HashMode, config: &CalibrationConfig) -> Self {
+        let batch: u64 = config.batch.max(1);
+        let mut current_hash: Hash = hasher.hash(algorithm.name().as_bytes());
+
+        for _ in 0..config.warmup_batches {
+            current_hash = hasher.extend_hash_chain(&current_hash, batch);
+        }
+
+        let mut hashes: u64 = 0;
+        let start: Instant = Instant::now();
+
+        // Always run at least one batch so a zero window still yields a rate.
+        loop {
+            current_hash = hasher.extend_hash_chain(&current_hash, batch);
+            hashes = hashes.saturating_add(batch);
+
+            if start.elapsed() >= config.window {
+                break;
+            }
+        }
+
+        let elapsed_us: u64 = u64::try_from(start.elapsed().as_micros()).unwrap_or(u64::MAX).max(1);
+
+        return Self::from_measurement(algorithm, mode, hashes, elapsed_us);
+    }
+
+    pub fn from_measurement(algorithm: Algorithm, mode: HashMode, hashes: u64, elapsed_us: u64) -> Self {
+        let rate: u128 = (hashes as u128).saturating_mul(1_000_000).checked_div(elapsed_us as u128).unwrap_or(0);
+
+        return Self {
+            algorithm,
+            mode,
+            hashes,
+            elapsed_us,
+            hashes_per_second: u64::try_from(rate).unwrap_or(u64::MAX),
+        };
+    }
+
+    /// Hashes that fit in `rev_duration_us`, keeping `headroom_percent` of the rev free. Never less than one.
+    pub fn hashes_per_rev(&self, rev_duration_us: u64, headroom_percent: u64) -> u64 {
+        let usable_percent: u128 = 100u128.saturating_sub(headroom_percent.min(100) as u128);
+        let hashes: u128 = (self.hashes_per_second as u128)
+            .saturating_mul(rev_duration_us as u128)
+            .saturating_mul(usable_percent)
+            .checked_div(100 * 1_000_000)
+            .unwrap_or(0);
+
+        return u64::try_from(hashes).unwrap_or(u64::MAX).max(1);
+    }
+
+    /// Hashes per rev for the default rev duration and headroom.
+    pub fn recommended_hashes_per_rev(&self) -> u64 {
+        return self.hashes_per_rev(DEFAULT_US_PER_REV, DEFAULT_CALIBRATION_HEADROOM_PERCENT);
+    }
+}
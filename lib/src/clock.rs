@@ -0,0 +1,135 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "std")]
+use std::{
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// Time source that paces PoH revs, in microseconds since the clock started.
+pub trait Clock {
+    fn now_us(&self) -> u64;
+
+    /// Returns once `now_us()` has reached `target_us`. Waits shorter than `spin_us` may busy-wait for precision.
+    ///
+    /// Called at every rev boundary. Without `pace` a real clock returns at once, a virtual one still moves to the target.
+    fn wait_until(&self, target_us: u64, spin_us: u64, pace: bool);
+}
+
+/// Real time from a monotonic `Instant`, sleeping or spinning between revs.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    start: Instant,
+}
+
+/// Time that only moves when a test advances it. Clones share the same time.
+#[derive(Debug, Default, Clone)]
+pub struct ManualClock {
+    now_us: Arc<AtomicU64>,
+}
+
+/// Virtual time that jumps straight to every wait target, so revs are produced as fast as they hash
+/// while timestamps still advance by exactly one rev duration per rev.
+#[derive(Debug, Default)]
+pub struct FastForwardClock {
+    now_us: AtomicU64,
+}
+
+/// Current system time in microseconds since the UNIX epoch, to anchor a [`Clock`] to wall time.
+#[cfg(feature = "std")]
+pub fn unix_time_us() -> u64 {
+    let elapsed: u128 = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros();
+    return u64::try_from(elapsed).unwrap_or(u64::MAX);
+}
+
+#[cfg(feature = "std")]
+impl MonotonicClock {
+    pub fn new() -> Self {
+        return Self { start: Instant::now() };
+    }
+
+    pub fn start(&self) -> Instant {
+        return self.start;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for MonotonicClock {
+    fn now_us(&self) -> u64 {
+        return u64::try_from(self.start.elapsed().as_micros()).unwrap_or(u64::MAX);
+    }
+
+    fn wait_until(&self, target_us: u64, spin_us: u64, pace: bool) {
+        let sleep_us: u64 = target_us.saturating_sub(self.now_us());
+        if !pace || sleep_us == 0 {
+            return;
+        }
+
+        // Use spin waiting for very short sleeps to improve precision.
+        if sleep_us < spin_us {
+            while self.now_us() < target_us {
+                // Insert a pause instruction to reduce CPU usage during spin-waiting.
+                #[cfg(target_arch = "x86_64")]
+                std::arch::x86_64::_mm_pause();
+            }
+        } else {
+            // Use normal sleep for longer durations.
+            thread::sleep(Duration::from_micros(sleep_us));
+        }
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn advance(&self, us: u64) {
+        self.now_us.fetch_add(us, Ordering::SeqCst);
+    }
+
+    pub fn set(&self, now_us: u64) {
+        self.now_us.store(now_us, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now_us(&self) -> u64 {
+        return self.now_us.load(Ordering::SeqCst);
+    }
+
+    // Never blocks, revs are produced at whatever time the test has set.
+    fn wait_until(&self, _target_us: u64, _spin_us: u64, _pace: bool) {}
+}
+
+impl FastForwardClock {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+}
+
+impl Clone for FastForwardClock {
+    fn clone(&self) -> Self {
+        return Self {
+            now_us: AtomicU64::new(self.now_us()),
+        };
+    }
+}
+
+impl Clock for FastForwardClock {
+    fn now_us(&self) -> u64 {
+        return self.now_us.load(Ordering::SeqCst);
+    }
+
+    fn wait_until(&self, target_us: u64, _spin_us: u64, _pace: bool) {
+        self.now_us.fetch_max(target_us, Ordering::SeqCst);
+    }
+}
@@ -1,18 +1,58 @@
+mod lanes;
+mod value;
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::hash::lanes::Sha256Step;
+
 use blake3::Hasher as Blake3Hasher;
-use ring::digest::{Context as RingContext, Digest, SHA256, digest};
+use ring::digest::{Context as RingContext, Digest, SHA256, SHA512_256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeError};
+use sha3::{Digest as Sha3Digest, Sha3_256};
 
-#[derive(Debug, Default, Eq, Clone, Copy, PartialEq)]
+pub use crate::hash::{
+    lanes::Lanes,
+    value::{HASH_BYTES, Hash, HashParseError},
+};
+
+// Most iterations a cancellable extension runs between checks of its cancel flag, whatever its report interval.
+pub const CANCEL_POLL_ITERATIONS: u64 = 1_024;
+
+#[derive(Debug, Default, Eq, Clone, Copy, PartialEq, Hash)]
 pub enum Algorithm {
     #[default]
     SHA256 = 0,
     BLAKE3 = 1,
+    SHA512_256 = 2,
+    SHA3_256 = 3,
 }
 
-impl From<u8> for Algorithm {
-    fn from(value: u8) -> Self {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlgorithmError {
+    UnknownId(u8),
+    UnknownName(String),
+}
+
+impl TryFrom<u8> for Algorithm {
+    type Error = AlgorithmError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         return match value {
-            1 => Algorithm::BLAKE3,
-            _ => Algorithm::SHA256, // Default to SHA-256 for any other value.
+            0 => Ok(Algorithm::SHA256),
+            1 => Ok(Algorithm::BLAKE3),
+            2 => Ok(Algorithm::SHA512_256),
+            3 => Ok(Algorithm::SHA3_256),
+            // Never fall back to a default, a ledger must be checked with the algorithm it was written with.
+            _ => Err(AlgorithmError::UnknownId(value)),
         };
     }
 }
@@ -23,92 +63,86 @@ impl From<Algorithm> for u8 {
     }
 }
 
-impl Algorithm {
-    pub fn name(&self) -> &'static str {
-        return match self {
-            Algorithm::SHA256 => "SHA-256",
-            Algorithm::BLAKE3 => "BLAKE3",
-        };
+impl FromStr for Algorithm {
+    type Err = AlgorithmError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Algorithm::ALL
+            .into_iter()
+            .find(|algorithm| algorithm.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| AlgorithmError::UnknownName(s.to_string()));
     }
 }
 
-#[derive(Default)]
-pub struct Hasher {
-    algorithm: Algorithm,
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return f.write_str(self.name());
+    }
 }
 
-impl Hasher {
-    pub fn new(algorithm: Algorithm) -> Self {
-        return Self { algorithm };
+// Serialized by name so saved configuration stays readable, and decoded as strictly as `FromStr`.
+impl Serialize for Algorithm {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(self.name());
     }
+}
 
-    pub fn algorithm(&self) -> Algorithm {
-        return self.algorithm;
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name: String = String::deserialize(deserializer)?;
+        return name.parse::<Algorithm>().map_err(DeError::custom);
     }
+}
 
-    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
-        return self.algorithm = algorithm;
-    }
+impl Algorithm {
+    pub const ALL: [Algorithm; 4] = [Algorithm::SHA256, Algorithm::BLAKE3, Algorithm::SHA512_256, Algorithm::SHA3_256];
 
-    pub fn algorithm_name(&self) -> &'static str {
-        return self.algorithm.name();
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Algorithm::SHA256 => "SHA-256",
+            Algorithm::BLAKE3 => "BLAKE3",
+            Algorithm::SHA512_256 => "SHA-512/256",
+            Algorithm::SHA3_256 => "SHA3-256",
+        };
     }
 
-    #[inline]
-    pub fn hash(&self, data: &[u8]) -> [u8; 32] {
-        return match self.algorithm {
-            Algorithm::BLAKE3 => *blake3::hash(data).as_bytes(),
-            Algorithm::SHA256 => {
-                let hash_result: Digest = digest(&SHA256, data);
-                let mut hash_bytes: [u8; 32] = [0u8; 32];
-                hash_bytes.copy_from_slice(hash_result.as_ref());
-                hash_bytes
-            }
-        };
+    pub fn id(&self) -> u8 {
+        return u8::from(*self);
     }
+}
 
-    #[inline]
-    pub fn embed_data(&self, previous_hash: &[u8; 32], data: &[u8]) -> [u8; 32] {
-        return match self.algorithm {
-            Algorithm::BLAKE3 => {
-                let mut hasher: Blake3Hasher = Blake3Hasher::new();
-                hasher.update(previous_hash);
-                hasher.update(data);
-                *hasher.finalize().as_bytes()
-            }
-            Algorithm::SHA256 => {
-                let mut context: RingContext = RingContext::new(&SHA256);
-                context.update(previous_hash);
-                context.update(data);
-                let result: Digest = context.finish();
-                let mut hash_bytes: [u8; 32] = [0u8; 32];
-                hash_bytes.copy_from_slice(result.as_ref());
-                hash_bytes
-            }
+impl Display for AlgorithmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            AlgorithmError::UnknownId(id) => write!(f, "Unknown hash algorithm id {}.", id),
+            AlgorithmError::UnknownName(name) => write!(f, "Unknown hash algorithm name '{}'.", name),
         };
     }
+}
 
-    #[inline(always)]
-    pub fn previous_hash(&self, hash: &[u8; 32]) -> [u8; 32] {
-        return match self.algorithm {
-            Algorithm::BLAKE3 => {
-                let mut hasher: Blake3Hasher = Blake3Hasher::new();
-                hasher.update(hash);
-                *hasher.finalize().as_bytes()
-            }
-            Algorithm::SHA256 => {
-                let mut context: RingContext = RingContext::new(&SHA256);
-                context.update(hash);
-                let result: Digest = context.finish();
-                let mut hash_bytes: [u8; 32] = [0u8; 32];
-                hash_bytes.copy_from_slice(result.as_ref());
-                hash_bytes
-            }
-        };
+impl Error for AlgorithmError {}
+
+/// A chain-hash backend: the hash / mixin / step primitives a Proof of History chain is built from.
+///
+/// Implementors only provide the three primitives; chain extension, verification and the
+/// throughput helper are derived from them, so a single verifier works for every backend.
+pub trait ChainHasher {
+    /// Hashes arbitrary data.
+    fn hash(&self, data: &[u8]) -> Hash;
+
+    /// Hashes the seed of a new chain.
+    fn genesis_hash(&self, seed: &[u8]) -> Hash {
+        return self.hash(seed);
     }
 
-    pub fn extend_hash_chain(&self, previous_hash: &[u8; 32], iterations: u64) -> [u8; 32] {
-        let mut current_hash: [u8; 32] = *previous_hash;
+    /// Mixes `data` into the chain at `previous_hash`.
+    fn embed_data(&self, previous_hash: &Hash, data: &[u8]) -> Hash;
+
+    /// Advances the chain by a single step.
+    fn previous_hash(&self, hash: &Hash) -> Hash;
+
+    fn extend_hash_chain(&self, previous_hash: &Hash, iterations: u64) -> Hash {
+        let mut current_hash: Hash = *previous_hash;
 
         // Short path for small iteration counts.
         if iterations < 8 {
@@ -143,8 +177,46 @@ impl Hasher {
         return current_hash;
     }
 
-    pub fn verify_hash_chain(&self, previous_hash: &[u8; 32], next_hash: &[u8; 32], iterations: u64, event_data: Option<&[u8]>) -> bool {
-        let mut expected_hash: [u8; 32] = *previous_hash;
+    /// Extends the chain like [`ChainHasher::extend_hash_chain`], reporting progress every `report_every`
+    /// iterations and stopping within [`CANCEL_POLL_ITERATIONS`] iterations once `cancel` is set.
+    ///
+    /// A cancelled run returns how far it got; extending its `current_hash` by the remaining
+    /// iterations gives the same result as an uninterrupted run.
+    fn extend_hash_chain_cancellable(
+        &self,
+        previous_hash: &Hash,
+        iterations: u64,
+        cancel: &AtomicBool,
+        report_every: u64,
+        on_progress: &mut dyn FnMut(&ChainProgress),
+    ) -> ChainExtension {
+        let chunk: u64 = report_every.max(1);
+        let mut next_report: u64 = chunk.min(iterations);
+        let mut progress: ChainProgress = ChainProgress {
+            iterations_done: 0,
+            current_hash: *previous_hash,
+        };
+
+        while progress.iterations_done < iterations {
+            if cancel.load(Ordering::Relaxed) {
+                return ChainExtension::Cancelled(progress);
+            }
+
+            // Polls the flag on its own stride, so a long report interval cannot delay cancellation.
+            let step: u64 = CANCEL_POLL_ITERATIONS.min(next_report.saturating_sub(progress.iterations_done));
+            progress.current_hash = self.extend_hash_chain(&progress.current_hash, step);
+            progress.iterations_done = progress.iterations_done.saturating_add(step);
+
+            if progress.iterations_done == next_report {
+                on_progress(&progress);
+                next_report = next_report.saturating_add(chunk).min(iterations);
+            }
+        }
+        return ChainExtension::Completed(progress.current_hash);
+    }
+
+    fn verify_hash_chain(&self, previous_hash: &Hash, next_hash: &Hash, iterations: u64, event_data: Option<&[u8]>) -> bool {
+        let mut expected_hash: Hash = *previous_hash;
 
         // If there's event data, hash it with the previous hash first.
         if let Some(data) = event_data {
@@ -154,21 +226,267 @@ impl Hasher {
         // Extend the hash chain by the specified number of iterations.
         expected_hash = self.extend_hash_chain(&expected_hash, iterations);
         // Constant-time comparison to prevent timing attacks.
-        return self.constant_time_eq(&expected_hash, next_hash);
+        return expected_hash == *next_hash;
+    }
+
+    /// Verifies many independent segments, one result per segment.
+    fn verify_hash_chains(&self, segments: &[Segment<'_>]) -> Vec<bool> {
+        return segments
+            .iter()
+            .map(|segment| self.verify_hash_chain(&segment.start, &segment.end, segment.iterations, segment.event))
+            .collect();
     }
 
-    pub fn compute_hashes(&self, iterations: u64) {
+    fn compute_hashes(&self, iterations: u64) {
         // Use a zero-initialized hash as starting point.
-        let zero_hash: [u8; 32] = [0u8; 32];
+        let zero_hash: Hash = Hash::default();
         let _ = self.extend_hash_chain(&zero_hash, iterations);
     }
+}
 
-    #[inline]
-    fn constant_time_eq(&self, a: &[u8; 32], b: &[u8; 32]) -> bool {
-        let mut result: u8 = 0;
-        for i in 0..32 {
-            result |= a[i] ^ b[i];
+/// How chain inputs are framed before hashing.
+///
+/// `Legacy` hashes raw inputs, so a mixin of empty data equals a plain tick. `DomainSeparatedV1`
+/// gives genesis, tick and mixin hashing their own domains: BLAKE3 uses a keyed hash with a key
+/// derived from the domain context, the SHA family uses tagged hashes `H(H(tag) || H(tag) || input)`.
+#[derive(Debug, Default, Eq, Clone, Copy, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum HashMode {
+    #[default]
+    Legacy = 0,
+    DomainSeparatedV1 = 1,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownHashMode(pub u8);
+
+impl TryFrom<u8> for HashMode {
+    type Error = UnknownHashMode;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        return match value {
+            0 => Ok(HashMode::Legacy),
+            1 => Ok(HashMode::DomainSeparatedV1),
+            _ => Err(UnknownHashMode(value)),
+        };
+    }
+}
+
+impl From<HashMode> for u8 {
+    fn from(value: HashMode) -> Self {
+        return value as u8;
+    }
+}
+
+impl Display for UnknownHashMode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return write!(f, "Unknown hash mode id {}.", self.0);
+    }
+}
+
+impl Error for UnknownHashMode {}
+
+/// Hashing domains of a domain-separated chain.
+#[derive(Debug, Eq, Clone, Copy, PartialEq, Hash)]
+pub enum Domain {
+    Genesis = 0,
+    Tick = 1,
+    Mixin = 2,
+}
+
+impl Domain {
+    pub const ALL: [Domain; 3] = [Domain::Genesis, Domain::Tick, Domain::Mixin];
+
+    /// Globally unique context string, also used as the BLAKE3 key derivation context.
+    pub fn context(&self) -> &'static str {
+        return match self {
+            Domain::Genesis => "rhythm 2025-06-01 PoH genesis v1",
+            Domain::Tick => "rhythm 2025-06-01 PoH tick v1",
+            Domain::Mixin => "rhythm 2025-06-01 PoH mixin v1",
+        };
+    }
+}
+
+/// How far a cancellable chain extension has got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainProgress {
+    pub iterations_done: u64,
+    pub current_hash: Hash,
+}
+
+impl ChainProgress {
+    /// Iterations still needed to reach `total`, resuming from `current_hash`.
+    pub fn remaining(&self, total: u64) -> u64 {
+        return total.saturating_sub(self.iterations_done);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainExtension {
+    Completed(Hash),
+    Cancelled(ChainProgress),
+}
+
+/// An independent hash-chain segment: `end` must be `iterations` steps after `start`, with `event` mixed in first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    pub start: Hash,
+    pub end: Hash,
+    pub iterations: u64,
+    pub event: Option<&'a [u8]>,
+}
+
+/// The default backend, selecting one of the built-in [`Algorithm`]s.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Hasher {
+    algorithm: Algorithm,
+    mode: HashMode,
+    // Per-domain BLAKE3 key or tag digest, indexed by `Domain`.
+    tags: [[u8; 32]; 3],
+}
+
+impl Hasher {
+    pub fn new(algorithm: Algorithm) -> Self {
+        return Self::with_mode(algorithm, HashMode::Legacy);
+    }
+
+    pub fn with_mode(algorithm: Algorithm, mode: HashMode) -> Self {
+        let mut hasher: Hasher = Self {
+            algorithm,
+            mode,
+            tags: [[0u8; 32]; 3],
+        };
+        hasher.derive_tags();
+        return hasher;
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        return self.algorithm;
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+        return self.derive_tags();
+    }
+
+    pub fn algorithm_name(&self) -> &'static str {
+        return self.algorithm.name();
+    }
+
+    pub fn mode(&self) -> HashMode {
+        return self.mode;
+    }
+
+    pub fn set_mode(&mut self, mode: HashMode) {
+        self.mode = mode;
+        return self.derive_tags();
+    }
+
+    /// Verifies segments in lock-step over the given number of lanes.
+    ///
+    /// Lanes only apply to SHA-256, other algorithms are verified one segment at a time. Results are
+    /// bit-identical for every lane width.
+    pub fn verify_hash_chains_with(&self, segments: &[Segment<'_>], lanes: Lanes) -> Vec<bool> {
+        if self.algorithm != Algorithm::SHA256 || lanes == Lanes::Scalar {
+            return segments
+                .iter()
+                .map(|segment| self.verify_hash_chain(&segment.start, &segment.end, segment.iterations, segment.event))
+                .collect();
+        }
+
+        let step: Sha256Step = match self.mode {
+            HashMode::Legacy => Sha256Step::legacy(),
+            HashMode::DomainSeparatedV1 => Sha256Step::tagged(&self.tags[Domain::Tick as usize]),
+        };
+        let starts: Vec<[u8; 32]> = segments
+            .iter()
+            .map(|segment| match segment.event {
+                Some(data) => self.embed_data(&segment.start, data).to_bytes(),
+                None => segment.start.to_bytes(),
+            })
+            .collect();
+
+        return lanes::verify_segments(&step, lanes, &starts, segments);
+    }
+
+    fn derive_tags(&mut self) {
+        for domain in Domain::ALL {
+            self.tags[domain as usize] = match self.algorithm {
+                Algorithm::BLAKE3 => blake3::derive_key(domain.context(), &[]),
+                _ => self.digest_parts(None, &[domain.context().as_bytes()]),
+            };
+        }
+    }
+
+    // Digests the concatenation of `parts` with the selected algorithm, framed for `domain` when domain-separated.
+    #[inline(always)]
+    fn digest_parts(&self, domain: Option<Domain>, parts: &[&[u8]]) -> [u8; 32] {
+        let tag: Option<&[u8; 32]> = match (self.mode, domain) {
+            (HashMode::DomainSeparatedV1, Some(domain)) => Some(&self.tags[domain as usize]),
+            _ => None,
+        };
+        let mut hash_bytes: [u8; 32] = [0u8; 32];
+
+        match self.algorithm {
+            Algorithm::BLAKE3 => {
+                let mut hasher: Blake3Hasher = match tag {
+                    Some(key) => Blake3Hasher::new_keyed(key),
+                    None => Blake3Hasher::new(),
+                };
+                for part in parts {
+                    hasher.update(part);
+                }
+                hash_bytes = *hasher.finalize().as_bytes();
+            }
+            Algorithm::SHA256 | Algorithm::SHA512_256 => {
+                let mut context: RingContext = RingContext::new(if self.algorithm == Algorithm::SHA256 { &SHA256 } else { &SHA512_256 });
+                if let Some(tag) = tag {
+                    context.update(tag);
+                    context.update(tag);
+                }
+                for part in parts {
+                    context.update(part);
+                }
+                let result: Digest = context.finish();
+                hash_bytes.copy_from_slice(result.as_ref());
+            }
+            Algorithm::SHA3_256 => {
+                let mut hasher: Sha3_256 = Sha3_256::new();
+                if let Some(tag) = tag {
+                    Sha3Digest::update(&mut hasher, tag);
+                    Sha3Digest::update(&mut hasher, tag);
+                }
+                for part in parts {
+                    Sha3Digest::update(&mut hasher, part);
+                }
+                hash_bytes.copy_from_slice(&hasher.finalize());
+            }
         }
-        return result == 0;
+        return hash_bytes;
+    }
+}
+
+impl ChainHasher for Hasher {
+    #[inline]
+    fn hash(&self, data: &[u8]) -> Hash {
+        return Hash::new(self.digest_parts(None, &[data]));
+    }
+
+    fn genesis_hash(&self, seed: &[u8]) -> Hash {
+        return Hash::new(self.digest_parts(Some(Domain::Genesis), &[seed]));
+    }
+
+    #[inline]
+    fn embed_data(&self, previous_hash: &Hash, data: &[u8]) -> Hash {
+        return Hash::new(self.digest_parts(Some(Domain::Mixin), &[previous_hash.as_bytes(), data]));
+    }
+
+    #[inline(always)]
+    fn previous_hash(&self, hash: &Hash) -> Hash {
+        return Hash::new(self.digest_parts(Some(Domain::Tick), &[hash.as_bytes()]));
+    }
+
+    fn verify_hash_chains(&self, segments: &[Segment<'_>]) -> Vec<bool> {
+        return self.verify_hash_chains_with(segments, Lanes::detect());
     }
 }
@@ -0,0 +1,313 @@
+// Multi-lane SHA-256 for verifying many independent hash-chain segments in lock-step.
+//
+// A chain step hashes a fixed-size input, so its SHA-256 message schedule is a single block whose
+// padding never changes. Each lane keeps its chain hash as eight state words and the block is
+// rebuilt from them every step, which lets the compiler map N lanes onto N-wide vector registers.
+
+use alloc::vec::Vec;
+use core::slice;
+
+use crate::hash::{Hash, Segment};
+
+use sha2::{compress256, digest::generic_array::GenericArray};
+
+const IV: [u32; 8] = [0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74,
+    0x80deb1fe, 0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d,
+    0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e,
+    0x92722c85, 0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5,
+    0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3, 0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Number of segments hashed in lock-step by the batch verifier.
+#[derive(Debug, Default, Eq, Clone, Copy, PartialEq, Hash)]
+pub enum Lanes {
+    /// One segment at a time through the regular backend, which uses SHA-NI when the CPU has it.
+    #[default]
+    Scalar,
+    /// One segment at a time through `sha2`'s compression function, which uses SHA-NI or the ARMv8 SHA-2
+    /// extension when the CPU has it. Unlike `Scalar` it reuses the fixed padding and tag midstate of every step.
+    ShaExt,
+    X4,
+    X8,
+    X16,
+}
+
+impl Lanes {
+    pub const ALL: [Lanes; 5] = [Lanes::Scalar, Lanes::ShaExt, Lanes::X4, Lanes::X8, Lanes::X16];
+
+    pub fn width(&self) -> usize {
+        return match self {
+            Lanes::Scalar | Lanes::ShaExt => 1,
+            Lanes::X4 => 4,
+            Lanes::X8 => 8,
+            Lanes::X16 => 16,
+        };
+    }
+
+    /// Picks the fastest lane width for this CPU.
+    ///
+    /// The multi-lane paths are portable code that the compiler autovectorizes, compiled with AVX2 or AVX-512
+    /// enabled for `X8` and `X16` once the CPU is known to have them. The order follows the poh crate's
+    /// `verify_hash_chains` benchmark (64 segments of 1,000 hashes) on an x86_64 host with SHA-NI, AVX2 and
+    /// AVX-512: `X16` 2.4 ms, `ShaExt` 5.2 ms, `X8` 6.1 ms, `Scalar` 8.6 ms and `X4` 35 ms. CPUs with SHA-NI (or
+    /// the ARMv8 SHA-2 extension) and no AVX-512 therefore take the `ShaExt` path.
+    pub fn detect() -> Self {
+        // Runtime feature detection needs std, without it the scalar path is always safe.
+        #[cfg(all(feature = "std", target_arch = "x86_64"))]
+        {
+            if std::arch::is_x86_feature_detected!("avx512f") {
+                return Lanes::X16;
+            }
+            if std::arch::is_x86_feature_detected!("sha") {
+                return Lanes::ShaExt;
+            }
+            if std::arch::is_x86_feature_detected!("avx2") {
+                return Lanes::X8;
+            }
+            // SSE2 is part of the x86_64 baseline.
+            return Lanes::X4;
+        }
+        #[cfg(all(feature = "std", target_arch = "aarch64"))]
+        {
+            if std::arch::is_aarch64_feature_detected!("sha2") {
+                return Lanes::ShaExt;
+            }
+            // NEON is part of the aarch64 baseline.
+            return Lanes::X4;
+        }
+        #[allow(unreachable_code)]
+        return Lanes::Scalar;
+    }
+}
+
+// Framing of one SHA-256 chain step: the state its final block is compressed from and the total message length.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Sha256Step {
+    init: [u32; 8],
+    length_bits: u32,
+}
+
+impl Sha256Step {
+    // Plain `SHA-256(hash)`.
+    pub(crate) fn legacy() -> Self {
+        return Self { init: IV, length_bits: 256 };
+    }
+
+    // Tagged `SHA-256(tag || tag || hash)`, starting from the midstate after the constant tag block.
+    pub(crate) fn tagged(tag: &[u8; 32]) -> Self {
+        let tag_words: [u32; 8] = to_words(tag);
+        let mut block: [[u32; 1]; 16] = [[0u32; 1]; 16];
+        let mut state: [[u32; 1]; 8] = [[0u32; 1]; 8];
+
+        for (i, word) in tag_words.iter().enumerate() {
+            block[i] = [*word];
+            block[i.saturating_add(8)] = [*word];
+            state[i] = [IV[i]];
+        }
+        compress(&mut state, &block);
+
+        return Self {
+            init: state.map(|word| word[0]),
+            length_bits: 768,
+        };
+    }
+}
+
+// Verifies `segments` whose event data has already been mixed into `starts`.
+pub(crate) fn verify_segments(step: &Sha256Step, lanes: Lanes, starts: &[[u8; 32]], segments: &[Segment<'_>]) -> Vec<bool> {
+    let ends: Vec<[u8; 32]> = match lanes {
+        Lanes::Scalar => run::<1>(step, starts, segments),
+        Lanes::ShaExt => run_sha_ext(step, starts, segments),
+        Lanes::X4 => run::<4>(step, starts, segments),
+        Lanes::X8 => {
+            #[cfg(all(feature = "std", target_arch = "x86_64"))]
+            if std::arch::is_x86_feature_detected!("avx2") {
+                // Safety: AVX2 support was just detected.
+                return finish(unsafe { run_x8_avx2(step, starts, segments) }, segments);
+            }
+            run::<8>(step, starts, segments)
+        }
+        Lanes::X16 => {
+            #[cfg(all(feature = "std", target_arch = "x86_64"))]
+            if std::arch::is_x86_feature_detected!("avx512f") {
+                // Safety: AVX-512F support was just detected.
+                return finish(unsafe { run_x16_avx512(step, starts, segments) }, segments);
+            }
+            run::<16>(step, starts, segments)
+        }
+    };
+    return finish(ends, segments);
+}
+
+fn finish(ends: Vec<[u8; 32]>, segments: &[Segment<'_>]) -> Vec<bool> {
+    return ends.iter().zip(segments).map(|(end, segment)| Hash::new(*end) == segment.end).collect();
+}
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+fn run_x8_avx2(step: &Sha256Step, starts: &[[u8; 32]], segments: &[Segment<'_>]) -> Vec<[u8; 32]> {
+    return run::<8>(step, starts, segments);
+}
+
+#[cfg(all(feature = "std", target_arch = "x86_64"))]
+#[target_feature(enable = "avx512f")]
+fn run_x16_avx512(step: &Sha256Step, starts: &[[u8; 32]], segments: &[Segment<'_>]) -> Vec<[u8; 32]> {
+    return run::<16>(step, starts, segments);
+}
+
+// Runs every segment on its own, through the compression function `sha2` picks for this CPU.
+fn run_sha_ext(step: &Sha256Step, starts: &[[u8; 32]], segments: &[Segment<'_>]) -> Vec<[u8; 32]> {
+    let mut block: [u8; 64] = [0u8; 64];
+    block[32] = 0x80;
+    block[60..].copy_from_slice(&step.length_bits.to_be_bytes());
+
+    return starts
+        .iter()
+        .zip(segments)
+        .map(|(start, segment)| {
+            let mut hash: [u8; 32] = *start;
+            for _ in 0..segment.iterations {
+                block[..32].copy_from_slice(&hash);
+                let mut state: [u32; 8] = step.init;
+                compress256(&mut state, slice::from_ref(GenericArray::from_slice(&block)));
+                hash = from_words(&state);
+            }
+            return hash;
+        })
+        .collect();
+}
+
+// Runs every segment to completion, refilling a lane with the next segment as soon as its current one finishes.
+#[inline(always)]
+fn run<const N: usize>(step: &Sha256Step, starts: &[[u8; 32]], segments: &[Segment<'_>]) -> Vec<[u8; 32]> {
+    let mut ends: Vec<[u8; 32]> = starts.to_vec();
+    let mut slots: [Option<usize>; N] = [None; N];
+    let mut remaining: [u64; N] = [0u64; N];
+    let mut hashes: [[u32; N]; 8] = [[0u32; N]; 8];
+    let mut next_segment: usize = 0;
+
+    loop {
+        // Hand idle lanes the next pending segment, skipping segments without iterations.
+        for lane in 0..N {
+            while slots[lane].is_none() && next_segment < segments.len() {
+                let index: usize = next_segment;
+                next_segment = next_segment.saturating_add(1);
+
+                if segments[index].iterations == 0 {
+                    continue;
+                }
+
+                let words: [u32; 8] = to_words(&starts[index]);
+                for (i, word) in words.iter().enumerate() {
+                    hashes[i][lane] = *word;
+                }
+                slots[lane] = Some(index);
+                remaining[lane] = segments[index].iterations;
+            }
+        }
+
+        // Advance all lanes together until the shortest active segment completes.
+        let burst: u64 = match (0..N).filter(|lane| slots[*lane].is_some()).map(|lane| remaining[lane]).min() {
+            Some(burst) => burst,
+            None => break,
+        };
+        for _ in 0..burst {
+            chain_step(step, &mut hashes);
+        }
+
+        for lane in 0..N {
+            if let Some(index) = slots[lane] {
+                remaining[lane] = remaining[lane].saturating_sub(burst);
+                if remaining[lane] == 0 {
+                    let mut words: [u32; 8] = [0u32; 8];
+                    for (i, word) in words.iter_mut().enumerate() {
+                        *word = hashes[i][lane];
+                    }
+                    ends[index] = from_words(&words);
+                    slots[lane] = None;
+                }
+            }
+        }
+    }
+    return ends;
+}
+
+// Replaces every lane's hash with the hash of itself.
+#[inline(always)]
+fn chain_step<const N: usize>(step: &Sha256Step, hashes: &mut [[u32; N]; 8]) {
+    let mut block: [[u32; N]; 16] = [[0u32; N]; 16];
+
+    block[..8].copy_from_slice(hashes);
+    block[8] = [0x8000_0000; N];
+    block[15] = [step.length_bits; N];
+
+    for (i, word) in hashes.iter_mut().enumerate() {
+        *word = [step.init[i]; N];
+    }
+    compress(hashes, &block);
+}
+
+// SHA-256 compression function over N independent lanes.
+#[inline(always)]
+#[allow(clippy::arithmetic_side_effects, clippy::needless_range_loop)]
+fn compress<const N: usize>(state: &mut [[u32; N]; 8], block: &[[u32; N]; 16]) {
+    let mut w: [[u32; N]; 64] = [[0u32; N]; 64];
+    w[..16].copy_from_slice(block);
+
+    for t in 16..64 {
+        for lane in 0..N {
+            let w15: u32 = w[t - 15][lane];
+            let w2: u32 = w[t - 2][lane];
+            let s0: u32 = w15.rotate_right(7) ^ w15.rotate_right(18) ^ (w15 >> 3);
+            let s1: u32 = w2.rotate_right(17) ^ w2.rotate_right(19) ^ (w2 >> 10);
+            w[t][lane] = w[t - 16][lane].wrapping_add(s0).wrapping_add(w[t - 7][lane]).wrapping_add(s1);
+        }
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for t in 0..64 {
+        for lane in 0..N {
+            let s1: u32 = e[lane].rotate_right(6) ^ e[lane].rotate_right(11) ^ e[lane].rotate_right(25);
+            let ch: u32 = (e[lane] & f[lane]) ^ (!e[lane] & g[lane]);
+            let temp1: u32 = h[lane].wrapping_add(s1).wrapping_add(ch).wrapping_add(K[t]).wrapping_add(w[t][lane]);
+            let s0: u32 = a[lane].rotate_right(2) ^ a[lane].rotate_right(13) ^ a[lane].rotate_right(22);
+            let maj: u32 = (a[lane] & b[lane]) ^ (a[lane] & c[lane]) ^ (b[lane] & c[lane]);
+            let temp2: u32 = s0.wrapping_add(maj);
+
+            h[lane] = g[lane];
+            g[lane] = f[lane];
+            f[lane] = e[lane];
+            e[lane] = d[lane].wrapping_add(temp1);
+            d[lane] = c[lane];
+            c[lane] = b[lane];
+            b[lane] = a[lane];
+            a[lane] = temp1.wrapping_add(temp2);
+        }
+    }
+
+    for (i, value) in [a, b, c, d, e, f, g, h].iter().enumerate() {
+        for lane in 0..N {
+            state[i][lane] = state[i][lane].wrapping_add(value[lane]);
+        }
+    }
+}
+
+fn to_words(bytes: &[u8; 32]) -> [u32; 8] {
+    let mut words: [u32; 8] = [0u32; 8];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(4)) {
+        *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    return words;
+}
+
+fn from_words(words: &[u32; 8]) -> [u8; 32] {
+    let mut bytes: [u8; 32] = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    return bytes;
+}
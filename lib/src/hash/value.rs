@@ -0,0 +1,198 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    error::Error,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
+    hash::{Hash as StdHash, Hasher as StdHasher},
+    str::FromStr,
+};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{Error as DeError, SeqAccess, Visitor},
+};
+
+pub const HASH_BYTES: usize = 32;
+
+/// A 32-byte chain hash.
+///
+/// Displays as lowercase hex, `{:#}` gives base58. `FromStr` accepts either. Equality is constant-time.
+#[derive(Default, Clone, Copy, PartialOrd, Ord)]
+pub struct Hash([u8; HASH_BYTES]);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HashParseError {
+    InvalidHex(String),
+    InvalidBase58(String),
+    InvalidLength(usize),
+}
+
+impl Hash {
+    pub const fn new(bytes: [u8; HASH_BYTES]) -> Self {
+        return Self(bytes);
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; HASH_BYTES] {
+        return &self.0;
+    }
+
+    pub const fn to_bytes(self) -> [u8; HASH_BYTES] {
+        return self.0;
+    }
+
+    pub fn to_hex(&self) -> String {
+        return hex::encode(self.0);
+    }
+
+    pub fn to_base58(&self) -> String {
+        return bs58::encode(self.0).into_string();
+    }
+
+    pub fn from_hex(s: &str) -> Result<Self, HashParseError> {
+        let bytes: Vec<u8> = hex::decode(s).map_err(|error| HashParseError::InvalidHex(error.to_string()))?;
+        return Self::try_from(bytes.as_slice());
+    }
+
+    pub fn from_base58(s: &str) -> Result<Self, HashParseError> {
+        let bytes: Vec<u8> = bs58::decode(s).into_vec().map_err(|error| HashParseError::InvalidBase58(error.to_string()))?;
+        return Self::try_from(bytes.as_slice());
+    }
+}
+
+impl From<[u8; HASH_BYTES]> for Hash {
+    fn from(bytes: [u8; HASH_BYTES]) -> Self {
+        return Self(bytes);
+    }
+}
+
+impl From<Hash> for [u8; HASH_BYTES] {
+    fn from(hash: Hash) -> Self {
+        return hash.0;
+    }
+}
+
+impl TryFrom<&[u8]> for Hash {
+    type Error = HashParseError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        return <[u8; HASH_BYTES]>::try_from(bytes).map(Self).map_err(|_| HashParseError::InvalidLength(bytes.len()));
+    }
+}
+
+impl AsRef<[u8]> for Hash {
+    fn as_ref(&self) -> &[u8] {
+        return &self.0;
+    }
+}
+
+impl AsMut<[u8]> for Hash {
+    fn as_mut(&mut self) -> &mut [u8] {
+        return &mut self.0;
+    }
+}
+
+impl PartialEq for Hash {
+    fn eq(&self, other: &Self) -> bool {
+        // Constant-time comparison to prevent timing attacks.
+        let mut result: u8 = 0;
+        for (a, b) in self.0.iter().zip(other.0.iter()) {
+            result |= a ^ b;
+        }
+        return result == 0;
+    }
+}
+
+impl Eq for Hash {}
+
+impl StdHash for Hash {
+    fn hash<S: StdHasher>(&self, state: &mut S) {
+        return self.0.hash(state);
+    }
+}
+
+impl Display for Hash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if f.alternate() {
+            return f.write_str(&self.to_base58());
+        }
+        return f.write_str(&self.to_hex());
+    }
+}
+
+impl Debug for Hash {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return write!(f, "Hash({})", self.to_hex());
+    }
+}
+
+impl FromStr for Hash {
+    type Err = HashParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Hex of 32 bytes is always 64 characters, base58 of 32 bytes never is.
+        if s.len() == HASH_BYTES.saturating_mul(2) {
+            return Self::from_hex(s);
+        }
+        return Self::from_base58(s);
+    }
+}
+
+impl Display for HashParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            HashParseError::InvalidHex(error) => write!(f, "Invalid hex hash: {}.", error),
+            HashParseError::InvalidBase58(error) => write!(f, "Invalid base58 hash: {}.", error),
+            HashParseError::InvalidLength(len) => write!(f, "Expected {} bytes, got {}.", HASH_BYTES, len),
+        };
+    }
+}
+
+impl Error for HashParseError {}
+
+// Hex in human-readable formats keeps JSON records wire-compatible, binary formats get the raw bytes.
+impl Serialize for Hash {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.serialize_str(&self.to_hex());
+        }
+        return serializer.serialize_bytes(&self.0);
+    }
+}
+
+impl<'de> Deserialize<'de> for Hash {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            let str: String = String::deserialize(deserializer)?;
+            return Hash::from_str(&str).map_err(DeError::custom);
+        }
+        return deserializer.deserialize_bytes(HashVisitor);
+    }
+}
+
+struct HashVisitor;
+
+impl<'de> Visitor<'de> for HashVisitor {
+    type Value = Hash;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return write!(f, "{} hash bytes", HASH_BYTES);
+    }
+
+    fn visit_bytes<E: DeError>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        return Hash::try_from(bytes).map_err(E::custom);
+    }
+
+    // Some binary formats encode byte strings as sequences.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes: [u8; HASH_BYTES] = [0u8; HASH_BYTES];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq.next_element()?.ok_or_else(|| DeError::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(DeError::invalid_length(HASH_BYTES.saturating_add(1), &self));
+        }
+        return Ok(Hash(bytes));
+    }
+}
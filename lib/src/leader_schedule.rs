@@ -0,0 +1,143 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{
+    hash::Hash,
+    metronome::{CycleSchedule, DEFAULT_NUM_CONSECUTIVE_LEADER_PHASES},
+};
+
+use rand_chacha::{
+    ChaCha8Rng,
+    rand_core::{RngCore, SeedableRng},
+};
+
+/// Stake-weighted leaders for every phase of one cycle, in runs of consecutive phases.
+///
+/// Nodes are drawn in stake-table key order from a ChaCha8 stream seeded with a PoH hash, and each draw is
+/// mapped onto the stake range with integer arithmetic only, so every node derives the same schedule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderSchedule<N> {
+    cycle: u64,
+    first_phase: u64,
+    phases: u64,
+    run_phases: u64,
+    // Leader of each run of `run_phases` phases, the last run may be shorter.
+    runs: Vec<N>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeaderScheduleError {
+    NoStake,
+    StakeOverflow,
+    ZeroRunLength,
+    CycleOverflow(u64),
+}
+
+impl<N: Clone + Ord> LeaderSchedule<N> {
+    /// Schedule for `cycle`, seeded with a hash fixed before it starts, such as the last hash of the previous cycle.
+    pub fn new(stakes: &BTreeMap<N, u64>, seed: &Hash, cycle: u64, schedule: &CycleSchedule) -> Result<Self, LeaderScheduleError> {
+        return Self::with_run_phases(stakes, seed, cycle, schedule, DEFAULT_NUM_CONSECUTIVE_LEADER_PHASES);
+    }
+
+    pub fn with_run_phases(stakes: &BTreeMap<N, u64>, seed: &Hash, cycle: u64, schedule: &CycleSchedule, run_phases: u64) -> Result<Self, LeaderScheduleError> {
+        if run_phases == 0 {
+            return Err(LeaderScheduleError::ZeroRunLength);
+        }
+
+        // Nodes without stake never lead.
+        let mut nodes: Vec<&N> = Vec::with_capacity(stakes.len());
+        let mut cumulative: Vec<u64> = Vec::with_capacity(stakes.len());
+        let mut total: u64 = 0;
+        for (node, stake) in stakes.iter().filter(|(_, stake)| **stake > 0) {
+            total = total.checked_add(*stake).ok_or(LeaderScheduleError::StakeOverflow)?;
+            nodes.push(node);
+            cumulative.push(total);
+        }
+        if total == 0 {
+            return Err(LeaderScheduleError::NoStake);
+        }
+
+        let first_phase: u64 = schedule.first_phase_of_cycle(cycle).ok_or(LeaderScheduleError::CycleOverflow(cycle))?;
+        let phases: u64 = schedule.phases_in_cycle(cycle);
+        let run_count: u64 = phases.div_ceil(run_phases);
+
+        let mut rng: ChaCha8Rng = ChaCha8Rng::from_seed(seed.to_bytes());
+        let mut runs: Vec<N> = Vec::with_capacity(usize::try_from(run_count).unwrap_or(0));
+        for _ in 0..run_count {
+            // Scales a uniform u64 onto `[0, total)`, the result is below `total` because the draw is below 2^64.
+            let point: u64 = ((rng.next_u64() as u128).saturating_mul(total as u128) >> 64) as u64;
+            let index: usize = cumulative.partition_point(|end| *end <= point);
+            runs.push(nodes[index].clone());
+        }
+
+        return Ok(Self {
+            cycle,
+            first_phase,
+            phases,
+            run_phases,
+            runs,
+        });
+    }
+
+    pub fn cycle(&self) -> u64 {
+        return self.cycle;
+    }
+
+    /// Absolute phase range `[first, first + phases)` covered by this schedule.
+    pub fn first_phase(&self) -> u64 {
+        return self.first_phase;
+    }
+
+    pub fn phases(&self) -> u64 {
+        return self.phases;
+    }
+
+    /// Leader of each run in order.
+    pub fn runs(&self) -> &[N] {
+        return &self.runs;
+    }
+
+    /// Leader of absolute phase `phase`, or `None` outside this cycle.
+    pub fn leader_at(&self, phase: u64) -> Option<&N> {
+        let offset: u64 = phase.checked_sub(self.first_phase).filter(|offset| *offset < self.phases)?;
+        let run: usize = usize::try_from(offset.checked_div(self.run_phases)?).ok()?;
+        return self.runs.get(run);
+    }
+
+    /// First phase at or after `from_phase` in this cycle that `node` leads.
+    pub fn next_leader_phase(&self, node: &N, from_phase: u64) -> Option<u64> {
+        let start: u64 = from_phase.max(self.first_phase);
+        let offset: u64 = start.checked_sub(self.first_phase).filter(|offset| *offset < self.phases)?;
+        let current_run: u64 = offset.checked_div(self.run_phases)?;
+
+        let run: u64 = self
+            .runs
+            .iter()
+            .enumerate()
+            .skip(usize::try_from(current_run).ok()?)
+            .find(|(_, leader)| *leader == node)
+            .map(|(run, _)| run as u64)?;
+
+        // Mid-run, the node already leads the current phase.
+        if run == current_run {
+            return Some(start);
+        }
+        return run.checked_mul(self.run_phases)?.checked_add(self.first_phase);
+    }
+}
+
+impl Display for LeaderScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            LeaderScheduleError::NoStake => write!(f, "Stake table has no staked nodes."),
+            LeaderScheduleError::StakeOverflow => write!(f, "Total stake overflows u64."),
+            LeaderScheduleError::ZeroRunLength => write!(f, "Leaders must lead at least one consecutive phase."),
+            LeaderScheduleError::CycleOverflow(cycle) => write!(f, "Cycle {} starts beyond the last representable phase.", cycle),
+        };
+    }
+}
+
+impl Error for LeaderScheduleError {}
@@ -1,2 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub mod calibration;
+pub mod clock;
 pub mod hash;
+pub mod leader_schedule;
+pub mod merkle;
 pub mod metronome;
+pub mod profile;
@@ -0,0 +1,135 @@
+use alloc::{vec, vec::Vec};
+
+use crate::hash::{ChainHasher, Hash};
+
+// Leaf and inner node hashes are prefixed with distinct bytes so a leaf can never be passed off as a node.
+pub const LEAF_PREFIX: u8 = 0x00;
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Merkle tree over a batch of events, whose root is mixed into PoH as a single event.
+///
+/// When a level has an odd number of nodes, the last node is promoted unchanged to the next level
+/// instead of being paired with a copy of itself, so no two distinct batches share a root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    // Level 0 holds the leaf hashes, the last level holds the root.
+    levels: Vec<Vec<Hash>>,
+}
+
+/// Inclusion proof for a single event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleProof {
+    pub index: usize,
+    pub leaf_count: usize,
+    // Sibling hashes from the leaf level upwards, skipping levels where the node was promoted.
+    pub siblings: Vec<Hash>,
+}
+
+impl MerkleTree {
+    pub fn new<H: ChainHasher, T: AsRef<[u8]>>(hasher: &H, events: &[T]) -> Self {
+        let leaves: Vec<Hash> = events.iter().map(|event| leaf_hash(hasher, event.as_ref())).collect();
+        let mut levels: Vec<Vec<Hash>> = vec![leaves];
+
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next: Vec<Hash> = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(hasher, left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two nodes"),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        return Self { levels };
+    }
+
+    /// Root to pass to `embed_data`. An empty tree has the root of an empty leaf set, `H("")`.
+    pub fn root<H: ChainHasher>(&self, hasher: &H) -> Hash {
+        return match self.levels.last().and_then(|level| level.first()) {
+            Some(root) => *root,
+            None => hasher.hash(&[]),
+        };
+    }
+
+    pub fn leaf_count(&self) -> usize {
+        return self.levels[0].len();
+    }
+
+    pub fn proof(&self, index: usize) -> Option<MerkleProof> {
+        if index >= self.leaf_count() {
+            return None;
+        }
+
+        let mut siblings: Vec<Hash> = Vec::with_capacity(self.levels.len());
+        let mut position: usize = index;
+
+        for level in &self.levels[..self.levels.len().saturating_sub(1)] {
+            if let Some(sibling) = level.get(position ^ 1) {
+                siblings.push(*sibling);
+            }
+            position /= 2;
+        }
+
+        return Some(MerkleProof {
+            index,
+            leaf_count: self.leaf_count(),
+            siblings,
+        });
+    }
+}
+
+impl MerkleProof {
+    pub fn verify<H: ChainHasher>(&self, hasher: &H, root: &Hash, event: &[u8]) -> bool {
+        return verify_proof(hasher, root, event, self);
+    }
+}
+
+/// Checks that `event` is included under `root` without access to the rest of the batch.
+pub fn verify_proof<H: ChainHasher>(hasher: &H, root: &Hash, event: &[u8], proof: &MerkleProof) -> bool {
+    if proof.index >= proof.leaf_count {
+        return false;
+    }
+
+    let mut current: Hash = leaf_hash(hasher, event);
+    let mut position: usize = proof.index;
+    let mut width: usize = proof.leaf_count;
+    let mut siblings = proof.siblings.iter();
+
+    while width > 1 {
+        // The last node of an odd level is promoted without a sibling.
+        let promoted: bool = position == width.saturating_sub(1) && !width.is_multiple_of(2);
+
+        if !promoted {
+            let sibling: &Hash = match siblings.next() {
+                Some(sibling) => sibling,
+                None => return false,
+            };
+            current = if position.is_multiple_of(2) {
+                node_hash(hasher, &current, sibling)
+            } else {
+                node_hash(hasher, sibling, &current)
+            };
+        }
+        position /= 2;
+        width = width.div_ceil(2);
+    }
+
+    return siblings.next().is_none() && current == *root;
+}
+
+pub fn leaf_hash<H: ChainHasher>(hasher: &H, event: &[u8]) -> Hash {
+    let mut input: Vec<u8> = Vec::with_capacity(event.len().saturating_add(1));
+    input.push(LEAF_PREFIX);
+    input.extend_from_slice(event);
+    return hasher.hash(&input);
+}
+
+pub fn node_hash<H: ChainHasher>(hasher: &H, left: &Hash, right: &Hash) -> Hash {
+    let mut input: [u8; 65] = [0u8; 65];
+    input[0] = NODE_PREFIX;
+    input[1..33].copy_from_slice(left.as_bytes());
+    input[33..].copy_from_slice(right.as_bytes());
+    return hasher.hash(&input);
+}
@@ -1,3 +1,12 @@
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::calibration::{Calibration, DEFAULT_CALIBRATION_HEADROOM_PERCENT};
+
+use serde::{Deserialize, Serialize};
+
 // Number of seconds per day.
 pub const DEFAULT_SECONDS_PER_DAY: u64 = 24 * 60 * 60;
 
@@ -48,3 +57,311 @@ pub const DEFAULT_BATCH_SIZE: usize = 64;
 
 // Use spinlock for precise timing under threshold.
 pub const DEFAULT_SPINLOCK_THRESHOLD_US: u64 = 250;
+
+// Length of the first warm-up cycle, doubled each cycle until it reaches phases per cycle.
+pub const DEFAULT_MINIMUM_PHASES_PER_CYCLE: u64 = 32;
+
+/// Timing parameters shared by PoH generation and verification, defaulting to the constants above.
+///
+/// Deserializing validates the result; missing fields take their default and unknown fields are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ClockConfigFields")]
+pub struct ClockConfig {
+    pub revs_per_second: u64,
+    pub us_per_rev: u64,
+    pub hashes_per_rev: u64,
+    // Sustained hash rate of the producing machine, see `Calibration`.
+    pub hashes_per_second: u64,
+    pub revs_per_phase: u64,
+    pub phases_per_cycle: u64,
+    pub spinlock_threshold_us: u64,
+    // Sleep or spin until each rev's target time. Unpaced generation only suits tests.
+    pub pace: bool,
+    // Start with short cycles that double in length, see `CycleSchedule`.
+    pub warmup: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockConfigError {
+    Zero(&'static str),
+    RevDurationMismatch { revs_per_second: u64, us_per_rev: u64 },
+    HashesExceedRev { hashes_per_rev: u64, hashes_per_second: u64, us_per_rev: u64 },
+    SpinlockExceedsRev { spinlock_threshold_us: u64, us_per_rev: u64 },
+    CycleOverflow,
+}
+
+/// Converts between revs, phases, cycles and estimated time since genesis.
+///
+/// With warm-up, cycle 0 has `DEFAULT_MINIMUM_PHASES_PER_CYCLE` phases and each following cycle doubles,
+/// until the next doubling would reach `phases_per_cycle`. From `first_normal_cycle` on every cycle is full length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CycleSchedule {
+    pub revs_per_phase: u64,
+    pub phases_per_cycle: u64,
+    pub us_per_rev: u64,
+    pub warmup: bool,
+    pub first_normal_cycle: u64,
+    pub first_normal_phase: u64,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        return Self {
+            revs_per_second: DEFAULT_REVS_PER_SECOND,
+            us_per_rev: DEFAULT_US_PER_REV,
+            hashes_per_rev: DEFAULT_HASHES_PER_REV,
+            hashes_per_second: DEFAULT_HASHES_PER_SECOND,
+            revs_per_phase: DEFAULT_REVS_PER_PHASE,
+            phases_per_cycle: DEFAULT_PHASES_PER_CYCLE,
+            spinlock_threshold_us: DEFAULT_SPINLOCK_THRESHOLD_US,
+            pace: true,
+            warmup: false,
+        };
+    }
+}
+
+impl ClockConfig {
+    pub fn validate(&self) -> Result<(), ClockConfigError> {
+        let fields: [(&'static str, u64); 6] = [
+            ("revs_per_second", self.revs_per_second),
+            ("us_per_rev", self.us_per_rev),
+            ("hashes_per_rev", self.hashes_per_rev),
+            ("hashes_per_second", self.hashes_per_second),
+            ("revs_per_phase", self.revs_per_phase),
+            ("phases_per_cycle", self.phases_per_cycle),
+        ];
+
+        if let Some((name, _)) = fields.iter().find(|(_, value)| *value == 0) {
+            return Err(ClockConfigError::Zero(name));
+        }
+
+        // A rev must last one second divided by the rev rate, rounded either way.
+        let second_us: u128 = (self.us_per_rev as u128).saturating_mul(self.revs_per_second as u128);
+        if second_us.abs_diff(1_000_000) >= self.revs_per_second as u128 {
+            return Err(ClockConfigError::RevDurationMismatch {
+                revs_per_second: self.revs_per_second,
+                us_per_rev: self.us_per_rev,
+            });
+        }
+
+        // The hashes of a rev must be computable within the rev at the calibrated rate.
+        let needed: u128 = (self.hashes_per_rev as u128).saturating_mul(1_000_000);
+        let available: u128 = (self.hashes_per_second as u128).saturating_mul(self.us_per_rev as u128);
+        if needed > available {
+            return Err(ClockConfigError::HashesExceedRev {
+                hashes_per_rev: self.hashes_per_rev,
+                hashes_per_second: self.hashes_per_second,
+                us_per_rev: self.us_per_rev,
+            });
+        }
+
+        if self.spinlock_threshold_us > self.us_per_rev {
+            return Err(ClockConfigError::SpinlockExceedsRev {
+                spinlock_threshold_us: self.spinlock_threshold_us,
+                us_per_rev: self.us_per_rev,
+            });
+        }
+
+        if self.revs_per_phase.checked_mul(self.phases_per_cycle).is_none() {
+            return Err(ClockConfigError::CycleOverflow);
+        }
+        return Ok(());
+    }
+
+    /// Takes the hash rate and the hashes-per-rev recommendation from a calibration run.
+    pub fn with_calibration(mut self, calibration: &Calibration) -> Self {
+        self.hashes_per_second = calibration.hashes_per_second;
+        self.hashes_per_rev = calibration.hashes_per_rev(self.us_per_rev, DEFAULT_CALIBRATION_HEADROOM_PERCENT);
+        return self;
+    }
+
+    pub fn revs_per_cycle(&self) -> u64 {
+        return self.revs_per_phase.saturating_mul(self.phases_per_cycle);
+    }
+
+    pub fn schedule(&self) -> CycleSchedule {
+        return CycleSchedule::new(self);
+    }
+}
+
+impl CycleSchedule {
+    pub fn new(config: &ClockConfig) -> Self {
+        let mut first_normal_cycle: u64 = 0;
+        let mut first_normal_phase: u64 = 0;
+
+        if config.warmup {
+            let mut length: u64 = DEFAULT_MINIMUM_PHASES_PER_CYCLE;
+            while length < config.phases_per_cycle {
+                first_normal_cycle = first_normal_cycle.saturating_add(1);
+                first_normal_phase = first_normal_phase.saturating_add(length);
+                length = length.saturating_mul(2);
+            }
+        }
+
+        return Self {
+            revs_per_phase: config.revs_per_phase,
+            phases_per_cycle: config.phases_per_cycle,
+            us_per_rev: config.us_per_rev,
+            warmup: config.warmup,
+            first_normal_cycle,
+            first_normal_phase,
+        };
+    }
+
+    pub fn phases_in_cycle(&self, cycle: u64) -> u64 {
+        if cycle < self.first_normal_cycle {
+            // Below the first normal cycle the shift stays under `phases_per_cycle`.
+            return DEFAULT_MINIMUM_PHASES_PER_CYCLE.checked_shl(cycle as u32).unwrap_or(self.phases_per_cycle);
+        }
+        return self.phases_per_cycle;
+    }
+
+    pub fn revs_in_cycle(&self, cycle: u64) -> Option<u64> {
+        return self.phases_in_cycle(cycle).checked_mul(self.revs_per_phase);
+    }
+
+    pub fn phase_of_rev(&self, rev: u64) -> u64 {
+        return rev.checked_div(self.revs_per_phase).unwrap_or(0);
+    }
+
+    pub fn cycle_of_rev(&self, rev: u64) -> u64 {
+        return self.cycle_of_phase(self.phase_of_rev(rev)).0;
+    }
+
+    /// Cycle containing `phase`, and the phase's offset within it.
+    pub fn cycle_of_phase(&self, phase: u64) -> (u64, u64) {
+        if phase < self.first_normal_phase {
+            // Warm-up cycle `k` starts at phase `MIN * (2^k - 1)`.
+            let cycle: u32 = (phase / DEFAULT_MINIMUM_PHASES_PER_CYCLE).saturating_add(1).ilog2();
+            let first: u64 = self.first_phase_of_cycle(cycle as u64).unwrap_or(0);
+            return (cycle as u64, phase.saturating_sub(first));
+        }
+
+        let normal_phase: u64 = phase.saturating_sub(self.first_normal_phase);
+        let cycle: u64 = normal_phase.checked_div(self.phases_per_cycle).unwrap_or(0);
+        let offset: u64 = normal_phase.checked_rem(self.phases_per_cycle).unwrap_or(0);
+        return (self.first_normal_cycle.saturating_add(cycle), offset);
+    }
+
+    pub fn first_phase_of_cycle(&self, cycle: u64) -> Option<u64> {
+        if cycle < self.first_normal_cycle {
+            let cycles_before: u64 = 1u64.checked_shl(cycle as u32)?.checked_sub(1)?;
+            return DEFAULT_MINIMUM_PHASES_PER_CYCLE.checked_mul(cycles_before);
+        }
+        return cycle
+            .checked_sub(self.first_normal_cycle)?
+            .checked_mul(self.phases_per_cycle)?
+            .checked_add(self.first_normal_phase);
+    }
+
+    pub fn last_phase_of_cycle(&self, cycle: u64) -> Option<u64> {
+        return self.first_phase_of_cycle(cycle)?.checked_add(self.phases_in_cycle(cycle))?.checked_sub(1);
+    }
+
+    pub fn first_rev_of_phase(&self, phase: u64) -> Option<u64> {
+        return phase.checked_mul(self.revs_per_phase);
+    }
+
+    pub fn last_rev_of_phase(&self, phase: u64) -> Option<u64> {
+        return self.first_rev_of_phase(phase)?.checked_add(self.revs_per_phase)?.checked_sub(1);
+    }
+
+    pub fn first_rev_of_cycle(&self, cycle: u64) -> Option<u64> {
+        return self.first_rev_of_phase(self.first_phase_of_cycle(cycle)?);
+    }
+
+    pub fn last_rev_of_cycle(&self, cycle: u64) -> Option<u64> {
+        return self.last_rev_of_phase(self.last_phase_of_cycle(cycle)?);
+    }
+
+    /// Estimated microseconds from genesis to the start of `rev`, assuming every rev took exactly `us_per_rev`.
+    pub fn us_at_rev(&self, rev: u64) -> Option<u64> {
+        return rev.checked_mul(self.us_per_rev);
+    }
+
+    /// Rev expected to be in progress `us` microseconds after genesis.
+    pub fn rev_at_us(&self, us: u64) -> u64 {
+        return us.checked_div(self.us_per_rev).unwrap_or(0);
+    }
+}
+
+impl Display for ClockConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            ClockConfigError::Zero(field) => write!(f, "Clock config field '{}' must not be zero.", field),
+            ClockConfigError::RevDurationMismatch { revs_per_second, us_per_rev } => {
+                write!(f, "A rev of {}us does not match {} revs per second.", us_per_rev, revs_per_second)
+            }
+            ClockConfigError::HashesExceedRev {
+                hashes_per_rev,
+                hashes_per_second,
+                us_per_rev,
+            } => write!(
+                f,
+                "{} hashes per rev do not fit in {}us at {} hashes per second.",
+                hashes_per_rev, us_per_rev, hashes_per_second
+            ),
+            ClockConfigError::SpinlockExceedsRev {
+                spinlock_threshold_us,
+                us_per_rev,
+            } => {
+                write!(f, "Spinlock threshold of {}us exceeds the {}us rev.", spinlock_threshold_us, us_per_rev)
+            }
+            ClockConfigError::CycleOverflow => write!(f, "Revs per cycle overflow."),
+        };
+    }
+}
+
+impl Error for ClockConfigError {}
+
+// Deserialization target, so loaded configs are checked before they become a `ClockConfig`.
+#[derive(Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ClockConfigFields {
+    revs_per_second: u64,
+    us_per_rev: u64,
+    hashes_per_rev: u64,
+    hashes_per_second: u64,
+    revs_per_phase: u64,
+    phases_per_cycle: u64,
+    spinlock_threshold_us: u64,
+    pace: bool,
+    warmup: bool,
+}
+
+impl Default for ClockConfigFields {
+    fn default() -> Self {
+        let config: ClockConfig = ClockConfig::default();
+        return Self {
+            revs_per_second: config.revs_per_second,
+            us_per_rev: config.us_per_rev,
+            hashes_per_rev: config.hashes_per_rev,
+            hashes_per_second: config.hashes_per_second,
+            revs_per_phase: config.revs_per_phase,
+            phases_per_cycle: config.phases_per_cycle,
+            spinlock_threshold_us: config.spinlock_threshold_us,
+            pace: config.pace,
+            warmup: config.warmup,
+        };
+    }
+}
+
+impl TryFrom<ClockConfigFields> for ClockConfig {
+    type Error = ClockConfigError;
+
+    fn try_from(fields: ClockConfigFields) -> Result<Self, Self::Error> {
+        let config: ClockConfig = ClockConfig {
+            revs_per_second: fields.revs_per_second,
+            us_per_rev: fields.us_per_rev,
+            hashes_per_rev: fields.hashes_per_rev,
+            hashes_per_second: fields.hashes_per_second,
+            revs_per_phase: fields.revs_per_phase,
+            phases_per_cycle: fields.phases_per_cycle,
+            spinlock_threshold_us: fields.spinlock_threshold_us,
+            pace: fields.pace,
+            warmup: fields.warmup,
+        };
+        config.validate()?;
+        return Ok(config);
+    }
+}
@@ -0,0 +1,175 @@
+use alloc::string::{String, ToString};
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+
+use crate::metronome::{ClockConfig, ClockConfigError, DEFAULT_DEV_PHASES_PER_CYCLE};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeError};
+
+/// Named timing preset a ledger is produced and verified under.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    #[default]
+    Production,
+    // Production cadence with short cycles, 400 ms * 8192 ~= 55 minutes.
+    Development,
+    // Tiny hash counts and no pacing, for integration tests.
+    Test,
+    // Clock loaded from a config file rather than a preset.
+    Custom,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProfileError {
+    UnknownName(String),
+    MissingClock,
+    ClockMismatch(Profile),
+    Clock(ClockConfigError),
+}
+
+/// A profile together with the clock it resolves to, as loaded from a config file and stored with a ledger.
+///
+/// Named profiles always carry their preset clock, only `Custom` takes an arbitrary one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "ProfileConfigFields")]
+pub struct ProfileConfig {
+    pub profile: Profile,
+    pub clock: ClockConfig,
+}
+
+impl Profile {
+    pub const ALL: [Profile; 4] = [Profile::Production, Profile::Development, Profile::Test, Profile::Custom];
+
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Profile::Production => "production",
+            Profile::Development => "development",
+            Profile::Test => "test",
+            Profile::Custom => "custom",
+        };
+    }
+
+    /// Preset clock of a named profile, `None` for `Custom`.
+    pub fn clock(&self) -> Option<ClockConfig> {
+        return match self {
+            Profile::Production => Some(ClockConfig::default()),
+            Profile::Development => Some(ClockConfig {
+                phases_per_cycle: DEFAULT_DEV_PHASES_PER_CYCLE,
+                ..ClockConfig::default()
+            }),
+            Profile::Test => Some(ClockConfig {
+                revs_per_second: 1_000,
+                us_per_rev: 1_000,
+                hashes_per_rev: 8,
+                revs_per_phase: 4,
+                phases_per_cycle: 8,
+                spinlock_threshold_us: 0,
+                pace: false,
+                ..ClockConfig::default()
+            }),
+            Profile::Custom => None,
+        };
+    }
+}
+
+impl FromStr for Profile {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Profile::ALL
+            .into_iter()
+            .find(|profile| profile.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| ProfileError::UnknownName(s.to_string()));
+    }
+}
+
+impl Display for Profile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return f.write_str(self.name());
+    }
+}
+
+impl Serialize for Profile {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return serializer.serialize_str(self.name());
+    }
+}
+
+impl<'de> Deserialize<'de> for Profile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name: String = String::deserialize(deserializer)?;
+        return name.parse::<Profile>().map_err(DeError::custom);
+    }
+}
+
+impl ProfileConfig {
+    /// Config of a named profile. Fails for `Custom`, which has no preset.
+    pub fn named(profile: Profile) -> Result<Self, ProfileError> {
+        let clock: ClockConfig = profile.clock().ok_or(ProfileError::MissingClock)?;
+        return Ok(Self { profile, clock });
+    }
+
+    pub fn custom(clock: ClockConfig) -> Result<Self, ProfileError> {
+        clock.validate().map_err(ProfileError::Clock)?;
+        return Ok(Self { profile: Profile::Custom, clock });
+    }
+}
+
+impl Default for ProfileConfig {
+    fn default() -> Self {
+        return Self {
+            profile: Profile::Production,
+            clock: ClockConfig::default(),
+        };
+    }
+}
+
+impl FromStr for ProfileConfig {
+    type Err = ProfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Self::named(s.parse::<Profile>()?);
+    }
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            ProfileError::UnknownName(name) => write!(f, "Unknown profile name: '{}'.", name),
+            ProfileError::MissingClock => write!(f, "A custom profile needs a clock config."),
+            ProfileError::ClockMismatch(profile) => write!(f, "Clock config does not match the '{}' profile.", profile),
+            ProfileError::Clock(error) => write!(f, "Invalid clock config: {}", error),
+        };
+    }
+}
+
+impl Error for ProfileError {}
+
+// Deserialization target, so a named profile can omit its clock and never override it.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileConfigFields {
+    profile: Profile,
+    #[serde(default)]
+    clock: Option<ClockConfig>,
+}
+
+impl TryFrom<ProfileConfigFields> for ProfileConfig {
+    type Error = ProfileError;
+
+    fn try_from(fields: ProfileConfigFields) -> Result<Self, Self::Error> {
+        return match (fields.profile.clock(), fields.clock) {
+            (Some(preset), None) => Ok(Self {
+                profile: fields.profile,
+                clock: preset,
+            }),
+            (Some(preset), Some(clock)) if clock == preset => Ok(Self { profile: fields.profile, clock }),
+            (Some(_), Some(_)) => Err(ProfileError::ClockMismatch(fields.profile)),
+            (None, Some(clock)) => Self::custom(clock),
+            (None, None) => Err(ProfileError::MissingClock),
+        };
+    }
+}
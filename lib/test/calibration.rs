@@ -0,0 +1,84 @@
+#[cfg(test)]
+mod calibration_operations {
+    use std::time::Duration;
+
+    use lib::{
+        calibration::{Calibration, CalibrationConfig, DEFAULT_CALIBRATION_HEADROOM_PERCENT},
+        hash::{Algorithm, HashMode},
+        metronome::{DEFAULT_HASHES_PER_REV, DEFAULT_HASHES_PER_SECOND, DEFAULT_US_PER_REV},
+    };
+
+    // Short window to keep the test suite fast.
+    const TEST_CONFIG: CalibrationConfig = CalibrationConfig {
+        window: Duration::from_millis(20),
+        batch: 1_000,
+        warmup_batches: 1,
+    };
+
+    #[test]
+    fn measures_every_algorithm() {
+        let calibrations: Vec<Calibration> = Calibration::measure_all(HashMode::Legacy, &TEST_CONFIG);
+
+        assert_eq!(calibrations.len(), Algorithm::ALL.len(), "Every algorithm should be measured.");
+
+        for (calibration, algorithm) in calibrations.iter().zip(Algorithm::ALL) {
+            assert_eq!(calibration.algorithm, algorithm, "Calibrations should follow Algorithm::ALL order.");
+            assert!(calibration.hashes >= TEST_CONFIG.batch, "At least one batch should be timed.");
+            assert!(calibration.hashes_per_second > 0, "Measured rate should be positive.");
+            assert!(calibration.recommended_hashes_per_rev() >= 1, "Recommendation should never be zero.");
+        }
+    }
+
+    #[test]
+    fn measures_the_configured_mode() {
+        for mode in [HashMode::Legacy, HashMode::DomainSeparatedV1] {
+            for calibration in Calibration::measure_all(mode, &TEST_CONFIG) {
+                assert_eq!(calibration.mode, mode, "Calibrations should record the mode they measured.");
+                assert!(calibration.hashes_per_second > 0, "Measured rate should be positive in every mode.");
+            }
+        }
+    }
+
+    #[test]
+    fn reference_rate_matches_default_constants() {
+        // The metronome defaults assume 2M hashes/s over a ~6ms rev.
+        let calibration: Calibration = Calibration::from_measurement(Algorithm::SHA256, HashMode::Legacy, DEFAULT_HASHES_PER_SECOND, 1_000_000);
+
+        assert_eq!(calibration.hashes_per_second, DEFAULT_HASHES_PER_SECOND, "Rate should be hashes over elapsed time.");
+        assert_eq!(
+            calibration.hashes_per_rev(DEFAULT_US_PER_REV, 0),
+            12_500,
+            "Full rev at 2M hashes/s should be 12,500 hashes."
+        );
+        assert_eq!(calibration.hashes_per_rev(DEFAULT_US_PER_REV, 100), 1, "No usable time should still allow one hash.");
+        assert!(
+            calibration.hashes_per_rev(DEFAULT_US_PER_REV, DEFAULT_CALIBRATION_HEADROOM_PERCENT) <= DEFAULT_HASHES_PER_REV,
+            "Recommendation with headroom should not exceed the default."
+        );
+    }
+
+    #[test]
+    fn saved_calibration_round_trips() {
+        let calibration: Calibration = Calibration::from_measurement(Algorithm::BLAKE3, HashMode::DomainSeparatedV1, 5_000_000, 1_250_000);
+        let json: String = serde_json::to_string(&calibration).unwrap();
+
+        assert!(json.contains("\"BLAKE3\""), "Algorithm should be saved by name.");
+        assert_eq!(
+            serde_json::from_str::<Calibration>(&json).unwrap(),
+            calibration,
+            "Calibration should round-trip through JSON."
+        );
+        assert!(
+            serde_json::from_str::<Calibration>(&json.replace("BLAKE3", "MD5")).is_err(),
+            "Unknown algorithm should be rejected."
+        );
+
+        let legacy: String = json.replace(",\"mode\":1", "");
+        assert_ne!(legacy, json, "The mode should be saved.");
+        assert_eq!(
+            serde_json::from_str::<Calibration>(&legacy).unwrap().mode,
+            HashMode::Legacy,
+            "Calibrations saved without a mode should have measured the legacy mode."
+        );
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod clock {
+    #[cfg(feature = "std")]
+    use std::time::Instant;
+
+    use lib::clock::{Clock, FastForwardClock, ManualClock};
+    #[cfg(feature = "std")]
+    use lib::clock::{MonotonicClock, unix_time_us};
+
+    #[test]
+    fn manual_clock_moves_only_when_advanced() {
+        let clock: ManualClock = ManualClock::new();
+        let handle: ManualClock = clock.clone();
+
+        clock.wait_until(1_000_000, 0, true);
+        assert_eq!(clock.now_us(), 0, "Waiting should not move a manual clock.");
+
+        handle.advance(250);
+        assert_eq!(clock.now_us(), 250, "Clones should share the same time.");
+        handle.set(10_000);
+        assert_eq!(clock.now_us(), 10_000, "Time should be settable.");
+    }
+
+    #[test]
+    fn fast_forward_clock_jumps_to_targets() {
+        let clock: FastForwardClock = FastForwardClock::new();
+
+        clock.wait_until(6_250, 250, true);
+        assert_eq!(clock.now_us(), 6_250, "Waiting should jump to the target.");
+        clock.wait_until(1_000, 250, true);
+        assert_eq!(clock.now_us(), 6_250, "Time should never go backwards.");
+
+        let copy: FastForwardClock = clock.clone();
+        copy.wait_until(12_500, 250, true);
+        assert_eq!(clock.now_us(), 6_250, "Clones should keep their own time.");
+
+        clock.wait_until(7_250, 250, false);
+        assert_eq!(clock.now_us(), 7_250, "Unpaced waits should still move virtual time.");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn monotonic_clock_waits_for_target() {
+        let clock: MonotonicClock = MonotonicClock::new();
+        let start: Instant = Instant::now();
+
+        clock.wait_until(2_000, 250, true);
+        assert!(clock.now_us() >= 2_000, "Clock should reach the target.");
+        assert!(start.elapsed().as_micros() >= 1_500, "Waiting should take real time.");
+
+        clock.wait_until(clock.now_us().saturating_add(100), 250, true);
+        clock.wait_until(0, 250, true);
+        assert!(clock.now_us() >= 2_100, "Spin waits should reach the target too.");
+
+        let before: Instant = Instant::now();
+        clock.wait_until(clock.now_us().saturating_add(10_000_000), 250, false);
+        assert!(before.elapsed().as_millis() < 1_000, "Unpaced waits should not sleep.");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn unix_time_follows_the_system_clock() {
+        let before: u64 = unix_time_us();
+        // 2020-01-01, in microseconds.
+        assert!(before > 1_577_836_800_000_000, "UNIX time should be counted from 1970.");
+        assert!(unix_time_us() >= before, "UNIX time should not run backwards between reads.");
+    }
+}
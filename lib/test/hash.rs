@@ -1,8 +1,14 @@
 #[cfg(test)]
 mod hash_operations {
-    use std::time::{Duration, Instant};
+    use std::{
+        sync::atomic::{AtomicBool, AtomicU64, Ordering},
+        time::{Duration, Instant},
+    };
 
-    use lib::hash::{Algorithm, Hasher};
+    use lib::hash::{
+        Algorithm, AlgorithmError, CANCEL_POLL_ITERATIONS, ChainExtension, ChainHasher, ChainProgress, Domain, Hash, HashMode, HashParseError, Hasher, Lanes, Segment,
+        UnknownHashMode,
+    };
 
     use blake3::Hasher as Blake3Hasher;
     use ring::digest::{Context, Digest, SHA256};
@@ -27,74 +33,269 @@ mod hash_operations {
         assert_eq!(hasher.algorithm(), Algorithm::BLAKE3, "Should be able to select BLAKE3.");
         assert_eq!(hasher.algorithm_name(), "BLAKE3", "Algorithm name should be BLAKE3.");
 
-        // Test invalid algorithm defaults to SHA-256.
-        hasher.set_algorithm(Algorithm::from(99));
+        hasher.set_algorithm(Algorithm::SHA512_256);
 
-        assert_eq!(hasher.algorithm(), Algorithm::SHA256, "Invalid algorithm should default to SHA-256.");
+        assert_eq!(hasher.algorithm(), Algorithm::SHA512_256, "Should be able to select SHA-512/256.");
+        assert_eq!(hasher.algorithm_name(), "SHA-512/256", "Algorithm name should be SHA-512/256.");
+
+        hasher.set_algorithm(Algorithm::SHA3_256);
+
+        assert_eq!(hasher.algorithm(), Algorithm::SHA3_256, "Should be able to select SHA3-256.");
+        assert_eq!(hasher.algorithm_name(), "SHA3-256", "Algorithm name should be SHA3-256.");
+
+        // Test invalid algorithm id is rejected instead of defaulting to SHA-256.
+        assert_eq!(
+            Algorithm::try_from(99),
+            Err(AlgorithmError::UnknownId(99)),
+            "Invalid algorithm id should be rejected."
+        );
 
         // Reset to SHA-256 for subsequent tests.
         hasher.set_algorithm(Algorithm::SHA256);
     }
 
+    #[test]
+    fn algorithm_id_round_trip() {
+        // Ids are part of the ledger format and must never change.
+        let expected_ids: [(Algorithm, u8); 4] = [(Algorithm::SHA256, 0), (Algorithm::BLAKE3, 1), (Algorithm::SHA512_256, 2), (Algorithm::SHA3_256, 3)];
+
+        for (algorithm, id) in expected_ids {
+            assert_eq!(u8::from(algorithm), id, "{} should have stable id {}.", algorithm, id);
+            assert_eq!(Algorithm::try_from(id), Ok(algorithm), "Id {} should decode to {}.", id, algorithm);
+        }
+        for id in 4..=u8::MAX {
+            assert!(Algorithm::try_from(id).is_err(), "Unknown id {} should not decode.", id);
+        }
+    }
+
+    #[test]
+    fn algorithm_name_round_trip() {
+        for algorithm in Algorithm::ALL {
+            assert_eq!(algorithm.name().parse::<Algorithm>(), Ok(algorithm), "{} should parse from its name.", algorithm);
+            assert_eq!(algorithm.to_string(), algorithm.name(), "Display should match the algorithm name.");
+        }
+
+        assert_eq!("blake3".parse::<Algorithm>(), Ok(Algorithm::BLAKE3), "Names should parse case-insensitively.");
+        assert_eq!(
+            "MD5".parse::<Algorithm>(),
+            Err(AlgorithmError::UnknownName("MD5".to_string())),
+            "Unknown names should be rejected."
+        );
+    }
+
+    #[test]
+    fn known_answer_vectors() {
+        // Digests of "abc" from the respective specifications.
+        let vectors: [(Algorithm, &str); 4] = [
+            (Algorithm::SHA256, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (Algorithm::BLAKE3, "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"),
+            (Algorithm::SHA512_256, "53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23"),
+            (Algorithm::SHA3_256, "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532"),
+        ];
+
+        for (algorithm, expected) in vectors {
+            let hasher: Hasher = Hasher::new(algorithm);
+            assert_eq!(hex::encode(hasher.hash(b"abc")), expected, "{} known-answer vector mismatch.", algorithm);
+            // Splitting the input across a mixin must not change the digest.
+            assert_eq!(
+                hex::encode(hasher.embed_data(&Hash::new([b'a'; 32]), b"bc")),
+                hex::encode(hasher.hash(&[&[b'a'; 32][..], b"bc"].concat())),
+                "{} mixin should hash the concatenated input.",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn hash_text_encodings() {
+        let hash: Hash = Hasher::default().hash(TEST_DATA);
+        let hex: String = hash.to_string();
+        let base58: String = format!("{:#}", hash);
+
+        assert_eq!(hex, hex::encode(hash.as_bytes()), "Display should be lowercase hex.");
+        assert_eq!(base58, hash.to_base58(), "Alternate display should be base58.");
+        assert_eq!(hex.parse::<Hash>(), Ok(hash), "Hex should parse back.");
+        assert_eq!(base58.parse::<Hash>(), Ok(hash), "Base58 should parse back.");
+        assert_eq!(hex.to_uppercase().parse::<Hash>(), Ok(hash), "Uppercase hex should parse.");
+        assert_eq!(Hash::from_hex("00ff"), Err(HashParseError::InvalidLength(2)), "Short input should be rejected.");
+        assert!(
+            matches!("0OIl".parse::<Hash>(), Err(HashParseError::InvalidBase58(_))),
+            "Invalid base58 should be rejected."
+        );
+        assert!(
+            matches!("zz".repeat(32).parse::<Hash>(), Err(HashParseError::InvalidHex(_))),
+            "Invalid hex should be rejected."
+        );
+    }
+
+    #[test]
+    fn hash_serde_formats() {
+        let hash: Hash = Hasher::default().hash(TEST_DATA);
+
+        // Human-readable formats carry hex, the same as the old record serializer.
+        let json: String = serde_json::to_string(&hash).unwrap();
+        assert_eq!(json, format!("\"{}\"", hash.to_hex()), "JSON should carry the hash as a hex string.");
+        assert_eq!(serde_json::from_str::<Hash>(&json).unwrap(), hash, "JSON should round-trip.");
+        assert!(serde_json::from_str::<Hash>("\"00ff\"").is_err(), "Wrong length should fail to deserialize.");
+
+        // Binary formats carry the raw bytes.
+        let bytes: Vec<u8> = bincode::serialize(&hash).unwrap();
+        assert_eq!(&bytes[bytes.len() - 32..], hash.as_bytes(), "Binary encoding should end with the raw bytes.");
+        assert_eq!(bytes.len(), 8 + 32, "Binary encoding should be a length-prefixed byte string.");
+        assert_eq!(bincode::deserialize::<Hash>(&bytes).unwrap(), hash, "Binary encoding should round-trip.");
+    }
+
+    #[test]
+    fn hash_ordering_and_equality() {
+        let low: Hash = Hash::new([0u8; 32]);
+        let mut high: Hash = low;
+        high.as_mut()[0] = 1;
+
+        assert!(low < high, "Hashes should order by their bytes.");
+        assert_ne!(low, high, "Hashes differing in one byte should not be equal.");
+        assert_eq!(Hash::from(high.to_bytes()), high, "Byte conversion should round-trip.");
+        assert_eq!(Hash::try_from(&high.as_bytes()[..]), Ok(high), "Slice conversion should round-trip.");
+        assert_eq!(format!("{:?}", low), format!("Hash({})", "0".repeat(64)), "Debug should show hex.");
+    }
+
     #[test]
     fn hash_function_basic() {
         // Test basic hash function with known values.
         let hasher_sha256: Hasher = Hasher::new(Algorithm::SHA256);
-        let result: [u8; 32] = hasher_sha256.hash(TEST_DATA);
+        let result: Hash = hasher_sha256.hash(TEST_DATA);
         let expected_sha256: Digest = ring::digest::digest(&SHA256, TEST_DATA);
 
         let mut expected_bytes: [u8; 32] = [0u8; 32];
         expected_bytes.copy_from_slice(expected_sha256.as_ref());
 
-        assert_eq!(result, expected_bytes, "Basic hash function should match expected SHA-256 output.");
+        assert_eq!(result, Hash::new(expected_bytes), "Basic hash function should match expected SHA-256 output.");
 
         // Test with BLAKE3.
         let hasher_blake3: Hasher = Hasher::new(Algorithm::BLAKE3);
-        let result_blake3: [u8; 32] = hasher_blake3.hash(TEST_DATA);
-        let expected_blake3: [u8; 32] = *blake3::hash(TEST_DATA).as_bytes();
+        let result_blake3: Hash = hasher_blake3.hash(TEST_DATA);
+        let expected_blake3: Hash = Hash::new(*blake3::hash(TEST_DATA).as_bytes());
 
         assert_eq!(result_blake3, expected_blake3, "Basic hash function should match expected BLAKE3 output.");
     }
 
     #[test]
     fn embed_data() {
-        let prev_hash: [u8; 32] = [b'1'; 32];
+        let prev_hash: Hash = Hash::new([b'1'; 32]);
 
         let hasher_sha256: Hasher = Hasher::new(Algorithm::SHA256);
-        let result: [u8; 32] = hasher_sha256.embed_data(&prev_hash, TEST_DATA);
+        let result: Hash = hasher_sha256.embed_data(&prev_hash, TEST_DATA);
 
         // Compute expected SHA-256 hash manually.
         let mut context: Context = Context::new(&SHA256);
-        context.update(&prev_hash);
+        context.update(prev_hash.as_bytes());
         context.update(TEST_DATA);
         let expected_digest: Digest = context.finish();
-        let mut expected: [u8; 32] = [0u8; 32];
-        expected.copy_from_slice(expected_digest.as_ref());
+        let mut expected: Hash = Hash::default();
+        expected.as_mut().copy_from_slice(expected_digest.as_ref());
 
         assert_eq!(result, expected, "embed_data should match manual SHA-256 calculation.");
 
         // Test BLAKE3.
         let hasher_blake3: Hasher = Hasher::new(Algorithm::BLAKE3);
-        let result_blake3: [u8; 32] = hasher_blake3.embed_data(&prev_hash, TEST_DATA);
+        let result_blake3: Hash = hasher_blake3.embed_data(&prev_hash, TEST_DATA);
 
         // Compute expected BLAKE3 hash manually.
         let mut hasher: Blake3Hasher = Blake3Hasher::new();
-        hasher.update(&prev_hash);
+        hasher.update(prev_hash.as_bytes());
         hasher.update(TEST_DATA);
-        let expected_blake3: [u8; 32] = *hasher.finalize().as_bytes();
+        let expected_blake3: Hash = Hash::new(*hasher.finalize().as_bytes());
 
         assert_eq!(result_blake3, expected_blake3, "embed_data should match manual BLAKE3 calculation.");
     }
 
+    #[test]
+    fn hash_mode_ids() {
+        assert_eq!(Hasher::default().mode(), HashMode::Legacy, "Hashers should default to the legacy mode.");
+        assert_eq!(HashMode::try_from(0), Ok(HashMode::Legacy), "Id 0 should decode to the legacy mode.");
+        assert_eq!(HashMode::try_from(1), Ok(HashMode::DomainSeparatedV1), "Id 1 should decode to domain separation v1.");
+        assert_eq!(HashMode::try_from(2), Err(UnknownHashMode(2)), "Unknown mode ids should be rejected.");
+        assert_eq!(u8::from(HashMode::DomainSeparatedV1), 1, "Mode ids must stay stable.");
+    }
+
+    #[test]
+    fn domain_separated_mixin_never_equals_tick() {
+        let prev_hash: Hash = Hash::new([b'1'; 32]);
+
+        for algorithm in Algorithm::ALL {
+            // The legacy framing is kept so old ledgers verify, collision included.
+            let legacy: Hasher = Hasher::new(algorithm);
+            assert_eq!(
+                legacy.embed_data(&prev_hash, &[]),
+                legacy.previous_hash(&prev_hash),
+                "{} legacy empty mixin should equal a tick.",
+                algorithm
+            );
+
+            let separated: Hasher = Hasher::with_mode(algorithm, HashMode::DomainSeparatedV1);
+            let tick: Hash = separated.previous_hash(&prev_hash);
+            assert_ne!(separated.embed_data(&prev_hash, &[]), tick, "{} empty mixin should differ from a tick.", algorithm);
+            assert_ne!(separated.genesis_hash(prev_hash.as_bytes()), tick, "{} genesis should differ from a tick.", algorithm);
+            assert_ne!(
+                separated.genesis_hash(prev_hash.as_bytes()),
+                separated.hash(prev_hash.as_bytes()),
+                "{} genesis should be domain separated.",
+                algorithm
+            );
+            assert_ne!(tick, legacy.previous_hash(&prev_hash), "{} modes should produce different chains.", algorithm);
+        }
+    }
+
+    #[test]
+    fn domain_separated_framing() {
+        let prev_hash: Hash = Hash::new([b'2'; 32]);
+
+        // SHA-256 uses tagged hashes.
+        let tag: Digest = ring::digest::digest(&SHA256, Domain::Tick.context().as_bytes());
+        let mut context: Context = Context::new(&SHA256);
+        context.update(tag.as_ref());
+        context.update(tag.as_ref());
+        context.update(prev_hash.as_bytes());
+        let expected: Digest = context.finish();
+        let separated: Hasher = Hasher::with_mode(Algorithm::SHA256, HashMode::DomainSeparatedV1);
+
+        assert_eq!(
+            &separated.previous_hash(&prev_hash).as_bytes()[..],
+            expected.as_ref(),
+            "SHA-256 tick should be a tagged hash."
+        );
+
+        // BLAKE3 uses a keyed hash with a key derived from the domain context.
+        let key: [u8; 32] = blake3::derive_key(Domain::Mixin.context(), &[]);
+        let mut hasher: Blake3Hasher = Blake3Hasher::new_keyed(&key);
+        hasher.update(prev_hash.as_bytes());
+        hasher.update(TEST_DATA);
+        let mut separated: Hasher = Hasher::new(Algorithm::BLAKE3);
+        separated.set_mode(HashMode::DomainSeparatedV1);
+
+        assert_eq!(
+            separated.embed_data(&prev_hash, TEST_DATA),
+            Hash::new(*hasher.finalize().as_bytes()),
+            "BLAKE3 mixin should be a keyed hash."
+        );
+
+        // Switching algorithm must re-derive the domain tags.
+        separated.set_algorithm(Algorithm::SHA256);
+        assert_eq!(
+            separated.previous_hash(&prev_hash),
+            Hasher::with_mode(Algorithm::SHA256, HashMode::DomainSeparatedV1).previous_hash(&prev_hash),
+            "Changing algorithm should keep the domain framing consistent."
+        );
+    }
+
     #[test]
     fn hash_chain_correctness() {
-        let seed: [u8; 32] = [b'0'; 32];
+        let seed: Hash = Hash::new([b'0'; 32]);
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
 
         // Compute our reference implementation result.
-        let hash1: [u8; 32] = manual_hash_chain_sha256(&seed, SMALL_ITERATIONS);
+        let hash1: Hash = manual_hash_chain_sha256(&seed, SMALL_ITERATIONS);
         // Call the function being tested.
-        let hash2: [u8; 32] = hasher.extend_hash_chain(&seed, SMALL_ITERATIONS);
+        let hash2: Hash = hasher.extend_hash_chain(&seed, SMALL_ITERATIONS);
 
         // Debug output to help diagnose failures.
         println!("Reference SHA-256: {:?}.", hash1);
@@ -105,15 +306,15 @@ mod hash_operations {
 
     #[test]
     fn hash_chain_with_different_iterations() {
-        let seed: [u8; 32] = [b'0'; 32];
+        let seed: Hash = Hash::new([b'0'; 32]);
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
 
         // Test with fewer iterations to start.
         let test_iterations: [u64; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
 
         for &iter in &test_iterations {
-            let expected: [u8; 32] = manual_hash_chain_sha256(&seed, iter);
-            let actual: [u8; 32] = hasher.extend_hash_chain(&seed, iter);
+            let expected: Hash = manual_hash_chain_sha256(&seed, iter);
+            let actual: Hash = hasher.extend_hash_chain(&seed, iter);
 
             // Debug output for failures.
             if expected != actual {
@@ -128,18 +329,18 @@ mod hash_operations {
     #[test]
     fn verify_hash_chain() {
         // Simplified verification test with fixed values.
-        let seed: [u8; 32] = [0x01; 32];
+        let seed: Hash = Hash::new([0x01; 32]);
         let iterations: u64 = 3;
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
 
         // Manually generate expected hash without using hasher.extend_hash_chain.
-        let mut expected_hash: [u8; 32] = seed;
+        let mut expected_hash: Hash = seed;
 
         for _ in 0..iterations {
             let mut context: Context = Context::new(&SHA256);
-            context.update(&expected_hash);
+            context.update(expected_hash.as_bytes());
             let result: Digest = context.finish();
-            expected_hash.copy_from_slice(result.as_ref());
+            expected_hash.as_mut().copy_from_slice(result.as_ref());
         }
 
         // Verify that this manually calculated hash can be verified.
@@ -149,8 +350,8 @@ mod hash_operations {
         );
 
         // Tamper with the hash to ensure verification fails.
-        let mut tampered_hash: [u8; 32] = expected_hash;
-        tampered_hash[0] ^= 1; // Flip a bit.
+        let mut tampered_hash: Hash = expected_hash;
+        tampered_hash.as_mut()[0] ^= 1; // Flip a bit.
 
         assert!(
             !hasher.verify_hash_chain(&seed, &tampered_hash, iterations, None),
@@ -159,21 +360,21 @@ mod hash_operations {
 
         // Testing with event data.
         let data: &[u8] = TEST_DATA;
-        let mut hash_with_event: [u8; 32] = seed;
+        let mut hash_with_event: Hash = seed;
 
         // First hash with data.
         let mut context: Context = Context::new(&SHA256);
-        context.update(&hash_with_event);
+        context.update(hash_with_event.as_bytes());
         context.update(data);
         let result: Digest = context.finish();
-        hash_with_event.copy_from_slice(result.as_ref());
+        hash_with_event.as_mut().copy_from_slice(result.as_ref());
 
         // Then continue the chain.
         for _ in 0..iterations {
             let mut context: Context = Context::new(&SHA256);
-            context.update(&hash_with_event);
+            context.update(hash_with_event.as_bytes());
             let result: Digest = context.finish();
-            hash_with_event.copy_from_slice(result.as_ref());
+            hash_with_event.as_mut().copy_from_slice(result.as_ref());
         }
 
         // Verify the chain with event data.
@@ -185,18 +386,18 @@ mod hash_operations {
 
     #[test]
     fn hash_chain_performance() {
-        let seed: [u8; 32] = [b'0'; 32];
+        let seed: Hash = Hash::new([b'0'; 32]);
 
         // Test SHA-256 performance.
         let hasher_sha256: Hasher = Hasher::new(Algorithm::SHA256);
         let start_sha256: Instant = Instant::now();
-        let sha256_result: [u8; 32] = hasher_sha256.extend_hash_chain(&seed, PERF_ITERATIONS);
+        let sha256_result: Hash = hasher_sha256.extend_hash_chain(&seed, PERF_ITERATIONS);
         let sha256_duration: Duration = start_sha256.elapsed();
 
         // Test BLAKE3 performance.
         let hasher_blake3: Hasher = Hasher::new(Algorithm::BLAKE3);
         let start_blake3: Instant = Instant::now();
-        let blake3_result: [u8; 32] = hasher_blake3.extend_hash_chain(&seed, PERF_ITERATIONS);
+        let blake3_result: Hash = hasher_blake3.extend_hash_chain(&seed, PERF_ITERATIONS);
         let blake3_duration: Duration = start_blake3.elapsed();
 
         // Prevent compiler from optimizing away the calculations.
@@ -208,16 +409,16 @@ mod hash_operations {
 
     #[test]
     fn hash_chain_determinism() {
-        let seed: [u8; 32] = [b'0'; 32];
+        let seed: Hash = Hash::new([b'0'; 32]);
         let iterations: u64 = 5; // Use a smaller number for reliability.
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
 
         // First generate a reference result.
-        let reference_result: [u8; 32] = hasher.extend_hash_chain(&seed, iterations);
+        let reference_result: Hash = hasher.extend_hash_chain(&seed, iterations);
 
         // Then check that multiple executions produce the same result.
         for i in 0..3 {
-            let result: [u8; 32] = hasher.extend_hash_chain(&seed, iterations);
+            let result: Hash = hasher.extend_hash_chain(&seed, iterations);
             assert_eq!(result, reference_result, "Hash chain iteration {} should be deterministic.", i);
         }
     }
@@ -226,7 +427,7 @@ mod hash_operations {
     fn constant_time_comparison() {
         // This test directly verifies the behavior of hasher.verify_hash_chain without
         // depending on the correctness of hasher.extend_hash_chain.
-        let seed: [u8; 32] = [b'a'; 32];
+        let seed: Hash = Hash::new([b'a'; 32]);
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
 
         // Directly test the verification functionality with simple values
@@ -234,19 +435,19 @@ mod hash_operations {
         let data: &[u8] = TEST_DATA;
 
         // Create a valid hash chain manually.
-        let mut expected_hash: [u8; 32] = seed;
+        let mut expected_hash: Hash = seed;
         let mut context: Context = Context::new(&SHA256);
-        context.update(&expected_hash);
+        context.update(expected_hash.as_bytes());
         context.update(data);
         let result: Digest = context.finish();
-        expected_hash.copy_from_slice(result.as_ref());
+        expected_hash.as_mut().copy_from_slice(result.as_ref());
 
         // Manually hash a few times to simulate a short chain.
         for _ in 0..5 {
             let mut context: Context = Context::new(&SHA256);
-            context.update(&expected_hash);
+            context.update(expected_hash.as_bytes());
             let result: Digest = context.finish();
-            expected_hash.copy_from_slice(result.as_ref());
+            expected_hash.as_mut().copy_from_slice(result.as_ref());
         }
 
         // Use hasher.verify_hash_chain to see if our manual chain matches.
@@ -256,8 +457,8 @@ mod hash_operations {
         );
 
         // Create a slightly modified hash to test failure case.
-        let mut tampered_hash: [u8; 32] = expected_hash;
-        tampered_hash[0] ^= 1; // Flip one bit.
+        let mut tampered_hash: Hash = expected_hash;
+        tampered_hash.as_mut()[0] ^= 1; // Flip one bit.
 
         assert!(
             !hasher.verify_hash_chain(&seed, &tampered_hash, 5, Some(data)),
@@ -268,38 +469,189 @@ mod hash_operations {
     #[test]
     fn hash_chain_with_large_iterations() {
         // Test with a larger number of iterations to ensure unrolled hashing works correctly.
-        let seed: [u8; 32] = [b'0'; 32];
+        let seed: Hash = Hash::new([b'0'; 32]);
         let iterations: u64 = 1_000;
 
         let hasher_sha256: Hasher = Hasher::new(Algorithm::SHA256);
-        let expected: [u8; 32] = manual_hash_chain_sha256(&seed, iterations);
-        let actual: [u8; 32] = hasher_sha256.extend_hash_chain(&seed, iterations);
+        let expected: Hash = manual_hash_chain_sha256(&seed, iterations);
+        let actual: Hash = hasher_sha256.extend_hash_chain(&seed, iterations);
 
         assert_eq!(actual, expected, "SHA-256 hash chain with {} iterations failed.", iterations);
 
         let hasher_blake3 = Hasher::new(Algorithm::BLAKE3);
-        let expected_blake3: [u8; 32] = manual_hash_chain_blake3(&seed, iterations);
-        let actual_blake3: [u8; 32] = hasher_blake3.extend_hash_chain(&seed, iterations);
+        let expected_blake3: Hash = manual_hash_chain_blake3(&seed, iterations);
+        let actual_blake3: Hash = hasher_blake3.extend_hash_chain(&seed, iterations);
 
         assert_eq!(actual_blake3, expected_blake3, "BLAKE3 hash chain with {} iterations failed.", iterations);
     }
 
+    #[test]
+    fn batch_verification_matches_scalar() {
+        let event: &[u8] = TEST_DATA;
+
+        for algorithm in Algorithm::ALL {
+            for mode in [HashMode::Legacy, HashMode::DomainSeparatedV1] {
+                let hasher: Hasher = Hasher::with_mode(algorithm, mode);
+                let mut segments: Vec<Segment<'_>> = Vec::new();
+
+                // Uneven iteration counts force lanes to be refilled at different times.
+                for (i, iterations) in [0u64, 1, 7, 8, 9, 33, 100, 5, 64, 2, 17, 250, 3, 0, 41, 12, 90, 1, 23].into_iter().enumerate() {
+                    let start: Hash = hasher.hash(&i.to_le_bytes());
+                    let event_data: Option<&[u8]> = if i % 3 == 0 { Some(event) } else { None };
+                    let mut end: Hash = match event_data {
+                        Some(data) => hasher.embed_data(&start, data),
+                        None => start,
+                    };
+
+                    end = hasher.extend_hash_chain(&end, iterations);
+                    // Tamper with every fifth segment.
+                    if i % 5 == 4 {
+                        end.as_mut()[i % 32] ^= 1;
+                    }
+                    segments.push(Segment {
+                        start,
+                        end,
+                        iterations,
+                        event: event_data,
+                    });
+                }
+
+                let expected: Vec<bool> = segments
+                    .iter()
+                    .map(|segment| hasher.verify_hash_chain(&segment.start, &segment.end, segment.iterations, segment.event))
+                    .collect();
+
+                assert!(expected.iter().any(|valid| *valid) && expected.iter().any(|valid| !*valid));
+                for lanes in Lanes::ALL {
+                    assert_eq!(
+                        hasher.verify_hash_chains_with(&segments, lanes),
+                        expected,
+                        "{} {:?} batch verification over {:?} should match the scalar path.",
+                        algorithm,
+                        mode,
+                        lanes
+                    );
+                }
+                assert_eq!(hasher.verify_hash_chains(&segments), expected, "Detected lanes should match the scalar path.");
+            }
+        }
+    }
+
+    #[test]
+    fn batch_verification_empty() {
+        let hasher: Hasher = Hasher::default();
+        assert!(hasher.verify_hash_chains(&[]).is_empty(), "No segments should yield no results.");
+        assert!(Lanes::ALL.contains(&Lanes::detect()), "Detected lanes should be a known width.");
+    }
+
+    #[test]
+    fn cancellable_extension_reports_progress() {
+        let seed: Hash = Hash::new([b'0'; 32]);
+        let hasher: Hasher = Hasher::default();
+        let cancel: AtomicBool = AtomicBool::new(false);
+        let mut reports: Vec<ChainProgress> = Vec::new();
+
+        let result: ChainExtension = hasher.extend_hash_chain_cancellable(&seed, 1_000, &cancel, 300, &mut |progress| reports.push(*progress));
+
+        assert_eq!(
+            result,
+            ChainExtension::Completed(hasher.extend_hash_chain(&seed, 1_000)),
+            "Uncancelled run should complete."
+        );
+        assert_eq!(
+            reports.iter().map(|progress| progress.iterations_done).collect::<Vec<u64>>(),
+            vec![300, 600, 900, 1_000],
+            "Progress should be reported every 300 iterations and at the end."
+        );
+        assert_eq!(reports[1].current_hash, hasher.extend_hash_chain(&seed, 600), "Reported hash should match the chain.");
+    }
+
+    #[test]
+    fn cancelled_extension_resumes_exactly() {
+        let seed: Hash = Hash::new([b'0'; 32]);
+        let total: u64 = 1_000;
+        let hasher: Hasher = Hasher::new(Algorithm::BLAKE3);
+        let cancel: AtomicBool = AtomicBool::new(false);
+
+        // Cancel from the progress callback after the second report.
+        let result: ChainExtension = hasher.extend_hash_chain_cancellable(&seed, total, &cancel, 128, &mut |progress| {
+            if progress.iterations_done >= 256 {
+                cancel.store(true, Ordering::Relaxed);
+            }
+        });
+        let progress: ChainProgress = match result {
+            ChainExtension::Cancelled(progress) => progress,
+            ChainExtension::Completed(_) => panic!("Extension should have been cancelled."),
+        };
+
+        assert_eq!(progress.iterations_done, 256, "Cancellation should stop at the next report boundary.");
+        assert_eq!(progress.remaining(total), 744, "Remaining iterations should account for completed work.");
+
+        let resumed: Hash = hasher.extend_hash_chain(&progress.current_hash, progress.remaining(total));
+        assert_eq!(resumed, hasher.extend_hash_chain(&seed, total), "Resuming should match an uninterrupted run.");
+
+        // A flag that is already set stops before any work.
+        let result: ChainExtension = hasher.extend_hash_chain_cancellable(&seed, total, &cancel, 128, &mut |_| {});
+        assert_eq!(
+            result,
+            ChainExtension::Cancelled(ChainProgress {
+                iterations_done: 0,
+                current_hash: seed,
+            }),
+            "Pre-cancelled run should do no work."
+        );
+    }
+
+    #[test]
+    fn cancellation_ignores_report_interval() {
+        let seed: Hash = Hash::new([b'0'; 32]);
+        let cancel: AtomicBool = AtomicBool::new(false);
+        let hasher: CancelAfter<'_> = CancelAfter {
+            cancel: &cancel,
+            steps: AtomicU64::new(0),
+            limit: 10,
+        };
+        let mut reports: u64 = 0;
+
+        // Neither interval would ever reach a report boundary before the chain ends.
+        for report_every in [u64::MAX, 0] {
+            cancel.store(false, Ordering::Relaxed);
+            hasher.steps.store(0, Ordering::Relaxed);
+            let result: ChainExtension = hasher.extend_hash_chain_cancellable(&seed, u64::MAX, &cancel, report_every, &mut |_| {
+                reports = reports.saturating_add(1);
+            });
+            let progress: ChainProgress = match result {
+                ChainExtension::Cancelled(progress) => progress,
+                ChainExtension::Completed(_) => panic!("Extension should have been cancelled."),
+            };
+
+            let expected: u64 = if report_every == 0 { hasher.limit } else { CANCEL_POLL_ITERATIONS };
+            assert_eq!(progress.iterations_done, expected, "Cancellation should be polled on its own stride.");
+            assert_eq!(
+                progress.current_hash,
+                Hasher::default().extend_hash_chain(&seed, expected),
+                "A cancelled run should report the hash it reached."
+            );
+        }
+        assert_eq!(reports, 10, "Only the one-iteration interval should have reported.");
+    }
+
     #[test]
     fn hash_boundary_conditions() {
         // Test with empty data.
         let hasher: Hasher = Hasher::new(Algorithm::SHA256);
         let empty_data: &[u8] = &[];
-        let result: [u8; 32] = hasher.hash(empty_data);
+        let result: Hash = hasher.hash(empty_data);
         let expected_sha256: Digest = ring::digest::digest(&SHA256, empty_data);
 
         let mut expected_bytes: [u8; 32] = [0u8; 32];
         expected_bytes.copy_from_slice(expected_sha256.as_ref());
 
-        assert_eq!(result, expected_bytes, "Hash of empty data should match expected output.");
+        assert_eq!(result, Hash::new(expected_bytes), "Hash of empty data should match expected output.");
 
         // Test with zero iterations.
-        let seed: [u8; 32] = [b'0'; 32];
-        let result_zero_iter: [u8; 32] = hasher.extend_hash_chain(&seed, 0);
+        let seed: Hash = Hash::new([b'0'; 32]);
+        let result_zero_iter: Hash = hasher.extend_hash_chain(&seed, 0);
 
         assert_eq!(result_zero_iter, seed, "Zero iterations should return the seed hash unchanged.");
     }
@@ -321,26 +673,100 @@ mod hash_operations {
         println!("BLAKE3 computation:  {:?} for {} iterations.", blake3_duration, PERF_ITERATIONS);
     }
 
+    #[test]
+    fn custom_chain_hasher_backend() {
+        // A third-party backend only provides the primitives, chain extension and verification come for free.
+        let backend: ReversedBlake3 = ReversedBlake3;
+        let seed: Hash = Hash::new([b'0'; 32]);
+
+        let mut expected: Hash = seed;
+        for _ in 0..SMALL_ITERATIONS {
+            expected = backend.previous_hash(&expected);
+        }
+
+        assert_eq!(
+            backend.extend_hash_chain(&seed, SMALL_ITERATIONS),
+            expected,
+            "Provided chain extension should use the backend step."
+        );
+        assert!(
+            backend.verify_hash_chain(&seed, &expected, SMALL_ITERATIONS, None),
+            "Provided verification should accept the backend's own chain."
+        );
+        assert!(
+            !Hasher::new(Algorithm::BLAKE3).verify_hash_chain(&seed, &expected, SMALL_ITERATIONS, None),
+            "A different backend should not verify the custom chain."
+        );
+    }
+
     // Reference implementation for SHA-256 testing.
-    fn manual_hash_chain_sha256(prev_hash: &[u8; 32], iterations: u64) -> [u8; 32] {
-        let mut current_hash: [u8; 32] = *prev_hash;
+    fn manual_hash_chain_sha256(prev_hash: &Hash, iterations: u64) -> Hash {
+        let mut current_hash: Hash = *prev_hash;
         for _ in 0..iterations {
             let mut context: Context = Context::new(&SHA256);
-            context.update(&current_hash);
+            context.update(current_hash.as_bytes());
             let result: Digest = context.finish();
-            current_hash.copy_from_slice(result.as_ref());
+            current_hash.as_mut().copy_from_slice(result.as_ref());
         }
         return current_hash;
     }
 
     // Reference implementation for BLAKE3 testing.
-    fn manual_hash_chain_blake3(prev_hash: &[u8; 32], iterations: u64) -> [u8; 32] {
-        let mut current_hash: [u8; 32] = *prev_hash;
+    fn manual_hash_chain_blake3(prev_hash: &Hash, iterations: u64) -> Hash {
+        let mut current_hash: Hash = *prev_hash;
         for _ in 0..iterations {
             let mut hasher: Blake3Hasher = Blake3Hasher::new();
-            hasher.update(&current_hash);
-            current_hash = *hasher.finalize().as_bytes();
+            hasher.update(current_hash.as_bytes());
+            current_hash = Hash::new(*hasher.finalize().as_bytes());
         }
         return current_hash;
     }
+
+    // Minimal custom backend used to exercise the trait's provided methods.
+    struct ReversedBlake3;
+
+    impl ChainHasher for ReversedBlake3 {
+        fn hash(&self, data: &[u8]) -> Hash {
+            let mut hash: [u8; 32] = *blake3::hash(data).as_bytes();
+            hash.reverse();
+            return Hash::new(hash);
+        }
+
+        fn embed_data(&self, previous_hash: &Hash, data: &[u8]) -> Hash {
+            let mut hasher: Blake3Hasher = Blake3Hasher::new();
+            hasher.update(previous_hash.as_bytes());
+            hasher.update(data);
+            let mut hash: [u8; 32] = *hasher.finalize().as_bytes();
+            hash.reverse();
+            return Hash::new(hash);
+        }
+
+        fn previous_hash(&self, hash: &Hash) -> Hash {
+            return self.hash(hash.as_bytes());
+        }
+    }
+
+    // Sets `cancel` once `limit` chain steps have been hashed.
+    struct CancelAfter<'a> {
+        cancel: &'a AtomicBool,
+        steps: AtomicU64,
+        limit: u64,
+    }
+
+    impl ChainHasher for CancelAfter<'_> {
+        fn hash(&self, data: &[u8]) -> Hash {
+            return Hasher::default().hash(data);
+        }
+
+        fn embed_data(&self, previous_hash: &Hash, data: &[u8]) -> Hash {
+            return Hasher::default().embed_data(previous_hash, data);
+        }
+
+        fn previous_hash(&self, hash: &Hash) -> Hash {
+            if self.steps.fetch_add(1, Ordering::Relaxed).saturating_add(1) >= self.limit {
+                self.cancel.store(true, Ordering::Relaxed);
+            }
+            return Hasher::default().previous_hash(hash);
+        }
+    }
 }
@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod leader_schedule {
+    use std::collections::BTreeMap;
+
+    use lib::{
+        hash::{ChainHasher, Hash, Hasher},
+        leader_schedule::{LeaderSchedule, LeaderScheduleError},
+        metronome::{ClockConfig, CycleSchedule, DEFAULT_NUM_CONSECUTIVE_LEADER_PHASES},
+    };
+
+    fn schedule() -> CycleSchedule {
+        return ClockConfig {
+            phases_per_cycle: 4_096,
+            ..ClockConfig::default()
+        }
+        .schedule();
+    }
+
+    fn stakes() -> BTreeMap<&'static str, u64> {
+        return BTreeMap::from([("alice", 600), ("bob", 300), ("carol", 100), ("dave", 0)]);
+    }
+
+    fn seed(cycle: u64) -> Hash {
+        return Hasher::default().hash(&cycle.to_le_bytes());
+    }
+
+    #[test]
+    fn deterministic() {
+        let first: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(1), 1, &schedule()).unwrap();
+        let second: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(1), 1, &schedule()).unwrap();
+        let other: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(2), 1, &schedule()).unwrap();
+
+        assert_eq!(first, second, "Same inputs should give the same schedule.");
+        assert_ne!(first.runs(), other.runs(), "Different seeds should give different schedules.");
+        assert_eq!(first.runs().len(), 1_024, "A cycle of 4096 phases should have 1024 runs of 4.");
+        // Pinned so a change in the draw would show up as a fork between nodes.
+        assert_eq!(
+            &first.runs()[..4],
+            &["carol", "alice", "carol", "bob"],
+            "The schedule should not change across releases."
+        );
+    }
+
+    #[test]
+    fn stake_weighted() {
+        let leaders: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(0), 0, &schedule()).unwrap();
+        let count = |node: &str| leaders.runs().iter().filter(|leader| **leader == node).count();
+
+        assert_eq!(count("dave"), 0, "Nodes without stake should never lead.");
+        assert!(count("alice") > count("bob") && count("bob") > count("carol"), "Runs should follow stake.");
+        assert!((540..=690).contains(&count("alice")), "Alice should lead about 60% of runs, got {}.", count("alice"));
+    }
+
+    #[test]
+    fn leader_lookup() {
+        let cycle_schedule: CycleSchedule = schedule();
+        let leaders: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(3), 3, &cycle_schedule).unwrap();
+        let first: u64 = cycle_schedule.first_phase_of_cycle(3).unwrap();
+
+        assert_eq!(leaders.first_phase(), first, "Schedule should start at the cycle's first phase.");
+        for phase in first..first + 4_096 {
+            let run: usize = ((phase - first) / DEFAULT_NUM_CONSECUTIVE_LEADER_PHASES) as usize;
+            assert_eq!(leaders.leader_at(phase), Some(&leaders.runs()[run]), "Each phase should be led by its run's leader.");
+        }
+        assert_eq!(leaders.leader_at(first - 1), None, "Phases before the cycle have no leader here.");
+        assert_eq!(leaders.leader_at(first + 4_096), None, "Phases after the cycle have no leader here.");
+    }
+
+    #[test]
+    fn next_leader_phase() {
+        let leaders: LeaderSchedule<&str> = LeaderSchedule::new(&stakes(), &seed(0), 0, &schedule()).unwrap();
+
+        for node in ["alice", "bob", "carol"] {
+            let mut phase: u64 = 0;
+            while let Some(next) = leaders.next_leader_phase(&node, phase) {
+                assert!(next >= phase, "Next phase should not be in the past.");
+                assert_eq!(leaders.leader_at(next), Some(&node), "Node should lead the phase it is told about.");
+                for skipped in phase..next {
+                    assert_ne!(leaders.leader_at(skipped), Some(&node), "No earlier phase should be led by the node.");
+                }
+                phase = next + 1;
+            }
+            assert!(phase > 0, "Every staked node should lead at least once.");
+        }
+        assert_eq!(leaders.next_leader_phase(&"dave", 0), None, "Unstaked nodes never lead.");
+        assert_eq!(leaders.next_leader_phase(&"alice", 4_096), None, "Nothing is scheduled after the cycle.");
+    }
+
+    #[test]
+    fn warmup_and_partial_runs() {
+        let warmup: CycleSchedule = ClockConfig {
+            phases_per_cycle: 4_096,
+            warmup: true,
+            ..ClockConfig::default()
+        }
+        .schedule();
+        let leaders: LeaderSchedule<&str> = LeaderSchedule::with_run_phases(&stakes(), &seed(1), 1, &warmup, 5).unwrap();
+
+        // Cycle 1 of the warm-up has 64 phases, 12 runs of 5 and one of 4.
+        assert_eq!(
+            (leaders.first_phase(), leaders.phases()),
+            (32, 64),
+            "Warm-up cycle bounds should come from the schedule."
+        );
+        assert_eq!(leaders.runs().len(), 13, "A trailing partial run should still get a leader.");
+        assert_eq!(leaders.leader_at(95), leaders.runs().last(), "The last phase should belong to the partial run.");
+    }
+
+    #[test]
+    fn invalid_inputs() {
+        let empty: BTreeMap<&str, u64> = BTreeMap::from([("dave", 0)]);
+        let overflow: BTreeMap<&str, u64> = BTreeMap::from([("alice", u64::MAX), ("bob", 1)]);
+
+        assert_eq!(
+            LeaderSchedule::new(&empty, &seed(0), 0, &schedule()),
+            Err(LeaderScheduleError::NoStake),
+            "Unstaked tables should be rejected."
+        );
+        assert_eq!(
+            LeaderSchedule::new(&overflow, &seed(0), 0, &schedule()),
+            Err(LeaderScheduleError::StakeOverflow),
+            "Overflowing stake should be rejected."
+        );
+        assert_eq!(
+            LeaderSchedule::with_run_phases(&stakes(), &seed(0), 0, &schedule(), 0),
+            Err(LeaderScheduleError::ZeroRunLength),
+            "Runs of zero phases should be rejected."
+        );
+    }
+}
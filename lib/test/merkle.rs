@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod merkle_operations {
+    use lib::{
+        hash::{Algorithm, ChainHasher, Hash, Hasher},
+        merkle::{self, MerkleProof, MerkleTree},
+    };
+
+    // Batch sizes covering empty, single, even, odd and non power of two trees.
+    const BATCH_SIZES: [usize; 8] = [0, 1, 2, 3, 5, 7, 8, 13];
+
+    fn events(count: usize) -> Vec<Vec<u8>> {
+        return (0..count).map(|i| format!("event {i}").into_bytes()).collect();
+    }
+
+    #[test]
+    fn every_leaf_proves_inclusion() {
+        for algorithm in Algorithm::ALL {
+            let hasher: Hasher = Hasher::new(algorithm);
+
+            for count in BATCH_SIZES {
+                let batch: Vec<Vec<u8>> = events(count);
+                let tree: MerkleTree = MerkleTree::new(&hasher, &batch);
+                let root: Hash = tree.root(&hasher);
+
+                assert_eq!(tree.leaf_count(), count, "Tree should hold one leaf per event.");
+                assert!(tree.proof(count).is_none(), "Proof past the last leaf should not exist.");
+
+                for (index, event) in batch.iter().enumerate() {
+                    let proof: MerkleProof = tree.proof(index).expect("Proof should exist for every leaf.");
+
+                    assert!(proof.siblings.len() <= usize::BITS as usize, "Proof should stay logarithmic.");
+                    assert!(
+                        merkle::verify_proof(&hasher, &root, event, &proof),
+                        "Proof should verify for {algorithm} with {count} leaves."
+                    );
+                    assert!(!proof.verify(&hasher, &root, b"forged event"), "Proof should reject a different event.");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_proofs_are_rejected() {
+        let hasher: Hasher = Hasher::default();
+        let batch: Vec<Vec<u8>> = events(7);
+        let tree: MerkleTree = MerkleTree::new(&hasher, &batch);
+        let root: Hash = tree.root(&hasher);
+        let proof: MerkleProof = tree.proof(2).unwrap();
+
+        let mut flipped: MerkleProof = proof.clone();
+        flipped.siblings[0].as_mut()[0] ^= 1;
+        assert!(!flipped.verify(&hasher, &root, &batch[2]), "Modified sibling should be rejected.");
+
+        let mut moved: MerkleProof = proof.clone();
+        moved.index = 3;
+        assert!(!moved.verify(&hasher, &root, &batch[2]), "Wrong index should be rejected.");
+
+        let mut truncated: MerkleProof = proof.clone();
+        truncated.siblings.pop();
+        assert!(!truncated.verify(&hasher, &root, &batch[2]), "Missing sibling should be rejected.");
+
+        let mut extended: MerkleProof = proof;
+        extended.siblings.push(Hash::default());
+        assert!(!extended.verify(&hasher, &root, &batch[2]), "Extra sibling should be rejected.");
+    }
+
+    #[test]
+    fn odd_node_is_promoted_not_duplicated() {
+        let hasher: Hasher = Hasher::default();
+        let three: MerkleTree = MerkleTree::new(&hasher, &events(3));
+        let mut padded: Vec<Vec<u8>> = events(3);
+        padded.push(padded[2].clone());
+        let four: MerkleTree = MerkleTree::new(&hasher, &padded);
+
+        let left: Hash = merkle::node_hash(&hasher, &merkle::leaf_hash(&hasher, b"event 0"), &merkle::leaf_hash(&hasher, b"event 1"));
+        let expected: Hash = merkle::node_hash(&hasher, &left, &merkle::leaf_hash(&hasher, b"event 2"));
+
+        assert_eq!(three.root(&hasher), expected, "Unpaired node should be promoted unchanged.");
+        assert_ne!(three.root(&hasher), four.root(&hasher), "Duplicating the last event should change the root.");
+    }
+
+    #[test]
+    fn leaves_and_nodes_are_domain_separated() {
+        let hasher: Hasher = Hasher::default();
+        let left: Hash = merkle::leaf_hash(&hasher, b"left");
+        let right: Hash = merkle::leaf_hash(&hasher, b"right");
+        let node: Hash = merkle::node_hash(&hasher, &left, &right);
+
+        // An inner node presented as a leaf must not hash to the same value.
+        let mut forged: Vec<u8> = Vec::with_capacity(64);
+        forged.extend_from_slice(left.as_bytes());
+        forged.extend_from_slice(right.as_bytes());
+
+        assert_ne!(merkle::leaf_hash(&hasher, &forged), node, "Leaf and node hashes should never collide.");
+        assert_eq!(
+            MerkleTree::new::<Hasher, Vec<u8>>(&hasher, &[]).root(&hasher),
+            hasher.hash(&[]),
+            "Empty tree should have the empty hash as root."
+        );
+    }
+
+    #[test]
+    fn root_is_embedded_as_single_event() {
+        let hasher: Hasher = Hasher::new(Algorithm::BLAKE3);
+        let batch: Vec<Vec<u8>> = events(100);
+        let root: Hash = MerkleTree::new(&hasher, &batch).root(&hasher);
+
+        let start: Hash = hasher.hash(b"seed");
+        let mixed: Hash = hasher.embed_data(&start, root.as_bytes());
+        let end: Hash = hasher.extend_hash_chain(&mixed, 10);
+
+        assert!(
+            hasher.verify_hash_chain(&start, &end, 10, Some(root.as_bytes())),
+            "Chain with an embedded Merkle root should verify."
+        );
+    }
+}
@@ -161,3 +161,262 @@ mod metronome_constants {
         );
     }
 }
+
+#[cfg(test)]
+mod clock_config {
+    use lib::{
+        calibration::Calibration,
+        hash::{Algorithm, HashMode},
+        metronome::{
+            ClockConfig, ClockConfigError, DEFAULT_HASHES_PER_REV, DEFAULT_HASHES_PER_SECOND, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE, DEFAULT_REVS_PER_SECOND,
+            DEFAULT_SPINLOCK_THRESHOLD_US, DEFAULT_US_PER_REV,
+        },
+    };
+
+    fn fast() -> ClockConfig {
+        return ClockConfig {
+            revs_per_second: 1_000,
+            us_per_rev: 1_000,
+            hashes_per_rev: 100,
+            revs_per_phase: 4,
+            phases_per_cycle: 2,
+            ..ClockConfig::default()
+        };
+    }
+
+    #[test]
+    fn default_matches_constants() {
+        let config: ClockConfig = ClockConfig::default();
+
+        assert_eq!(config.revs_per_second, DEFAULT_REVS_PER_SECOND, "Default revs per second should match the constant.");
+        assert_eq!(config.us_per_rev, DEFAULT_US_PER_REV, "Default rev duration should match the constant.");
+        assert_eq!(config.hashes_per_rev, DEFAULT_HASHES_PER_REV, "Default hashes per rev should match the constant.");
+        assert_eq!(config.hashes_per_second, DEFAULT_HASHES_PER_SECOND, "Default hash rate should match the constant.");
+        assert_eq!(config.revs_per_phase, DEFAULT_REVS_PER_PHASE, "Default revs per phase should match the constant.");
+        assert_eq!(
+            config.phases_per_cycle, DEFAULT_PHASES_PER_CYCLE,
+            "Default phases per cycle should match the constant."
+        );
+        assert_eq!(
+            config.spinlock_threshold_us, DEFAULT_SPINLOCK_THRESHOLD_US,
+            "Default spinlock threshold should match the constant."
+        );
+        assert_eq!(config.validate(), Ok(()), "Default config should be valid.");
+    }
+
+    #[test]
+    fn rev_positions() {
+        let config: ClockConfig = fast();
+
+        assert_eq!(config.revs_per_cycle(), 8, "A cycle should span revs_per_phase * phases_per_cycle revs.");
+        assert_eq!(config.schedule().phase_of_rev(9), 2, "Rev 9 should fall in phase 2.");
+        assert_eq!(config.schedule().cycle_of_rev(9), 1, "Rev 9 should fall in cycle 1.");
+    }
+
+    #[test]
+    fn validation_errors() {
+        assert_eq!(
+            ClockConfig { hashes_per_rev: 0, ..fast() }.validate(),
+            Err(ClockConfigError::Zero("hashes_per_rev")),
+            "Zero fields should be rejected."
+        );
+        assert_eq!(
+            ClockConfig { us_per_rev: 900, ..fast() }.validate(),
+            Err(ClockConfigError::RevDurationMismatch {
+                revs_per_second: 1_000,
+                us_per_rev: 900,
+            }),
+            "Rev duration should match the rev rate."
+        );
+        assert_eq!(
+            ClockConfig { hashes_per_rev: 2_001, ..fast() }.validate(),
+            Err(ClockConfigError::HashesExceedRev {
+                hashes_per_rev: 2_001,
+                hashes_per_second: DEFAULT_HASHES_PER_SECOND,
+                us_per_rev: 1_000,
+            }),
+            "Hashes per rev should fit in the rev at the hash rate."
+        );
+        assert_eq!(
+            ClockConfig {
+                spinlock_threshold_us: 1_001,
+                ..fast()
+            }
+            .validate(),
+            Err(ClockConfigError::SpinlockExceedsRev {
+                spinlock_threshold_us: 1_001,
+                us_per_rev: 1_000,
+            }),
+            "Spinlock threshold should not exceed the rev."
+        );
+        assert_eq!(
+            ClockConfig {
+                revs_per_phase: u64::MAX,
+                ..fast()
+            }
+            .validate(),
+            Err(ClockConfigError::CycleOverflow),
+            "Revs per cycle should not overflow."
+        );
+        // 1_000_000 / 160 = 6250 exactly, 1_000_000 / 3 rounds either way.
+        assert!(
+            ClockConfig {
+                revs_per_second: 3,
+                us_per_rev: 333_334,
+                spinlock_threshold_us: 0,
+                ..ClockConfig::default()
+            }
+            .validate()
+            .is_ok(),
+            "Rounded rev durations should be accepted."
+        );
+    }
+
+    #[test]
+    fn serde_round_trip_and_partial_load() {
+        let config: ClockConfig = fast();
+        let json: String = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<ClockConfig>(&json).unwrap(), config, "Config should round-trip through JSON.");
+
+        let partial: ClockConfig = serde_json::from_str(r#"{"hashes_per_rev": 10000}"#).unwrap();
+        assert_eq!(
+            partial,
+            ClockConfig {
+                hashes_per_rev: 10_000,
+                ..ClockConfig::default()
+            },
+            "Missing fields should take their default."
+        );
+
+        assert!(
+            serde_json::from_str::<ClockConfig>(r#"{"hashes_per_revv": 10000}"#).is_err(),
+            "Unknown fields should be rejected."
+        );
+        let error: String = serde_json::from_str::<ClockConfig>(r#"{"revs_per_phase": 0}"#).unwrap_err().to_string();
+        assert!(error.contains("revs_per_phase"), "Invalid configs should be rejected on load: {}", error);
+    }
+
+    #[test]
+    fn calibrated_config() {
+        let calibration: Calibration = Calibration::from_measurement(Algorithm::SHA256, HashMode::Legacy, 4_000_000, 1_000_000);
+        let config: ClockConfig = ClockConfig::default().with_calibration(&calibration);
+
+        assert_eq!(config.hashes_per_second, 4_000_000, "Hash rate should come from the calibration.");
+        assert_eq!(config.hashes_per_rev, 22_500, "Hashes per rev should keep 10% of the rev free.");
+        assert_eq!(config.validate(), Ok(()), "Calibrated config should be valid.");
+    }
+}
+
+#[cfg(test)]
+mod cycle_schedule {
+    use lib::metronome::{ClockConfig, CycleSchedule, DEFAULT_MINIMUM_PHASES_PER_CYCLE, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE, DEFAULT_US_PER_REV};
+
+    fn warmup(phases_per_cycle: u64) -> CycleSchedule {
+        return ClockConfig {
+            phases_per_cycle,
+            warmup: true,
+            ..ClockConfig::default()
+        }
+        .schedule();
+    }
+
+    #[test]
+    fn normal_cycles() {
+        let schedule: CycleSchedule = ClockConfig::default().schedule();
+        let revs_per_cycle: u64 = DEFAULT_REVS_PER_PHASE * DEFAULT_PHASES_PER_CYCLE;
+
+        assert_eq!(schedule.first_normal_cycle, 0, "Without warm-up every cycle is normal.");
+        assert_eq!(schedule.phase_of_rev(DEFAULT_REVS_PER_PHASE * 3 + 5), 3, "Rev should map to its phase.");
+        assert_eq!(schedule.cycle_of_rev(revs_per_cycle - 1), 0, "Last rev of cycle 0 should be in cycle 0.");
+        assert_eq!(schedule.cycle_of_rev(revs_per_cycle), 1, "First rev of cycle 1 should be in cycle 1.");
+        assert_eq!(
+            schedule.first_rev_of_cycle(2),
+            Some(2 * revs_per_cycle),
+            "Cycle 2 should start after two full cycles."
+        );
+        assert_eq!(schedule.last_rev_of_cycle(2), Some(3 * revs_per_cycle - 1), "Cycle 2 should end before cycle 3.");
+        assert_eq!(
+            schedule.first_rev_of_phase(7),
+            Some(7 * DEFAULT_REVS_PER_PHASE),
+            "Phase 7 should start at its first rev."
+        );
+        assert_eq!(
+            schedule.last_rev_of_phase(7),
+            Some(8 * DEFAULT_REVS_PER_PHASE - 1),
+            "Phase 7 should end before phase 8."
+        );
+        assert_eq!(schedule.revs_in_cycle(5), Some(revs_per_cycle), "Normal cycles should be full length.");
+    }
+
+    #[test]
+    fn warmup_cycles_double() {
+        let schedule: CycleSchedule = warmup(256);
+        let min: u64 = DEFAULT_MINIMUM_PHASES_PER_CYCLE;
+
+        // 32, 64 and 128 phases, then 256 from cycle 3.
+        assert_eq!(schedule.first_normal_cycle, 3, "Warm-up should stop before reaching the normal length.");
+        assert_eq!(schedule.first_normal_phase, 7 * min, "Normal cycles should start after the warm-up phases.");
+        assert_eq!(schedule.phases_in_cycle(0), min, "The first cycle should be the minimum length.");
+        assert_eq!(schedule.phases_in_cycle(2), 4 * min, "Warm-up cycles should double.");
+        assert_eq!(schedule.phases_in_cycle(3), 256, "Cycles after warm-up should be full length.");
+
+        assert_eq!(schedule.cycle_of_phase(min - 1), (0, min - 1), "Last phase of cycle 0.");
+        assert_eq!(schedule.cycle_of_phase(min), (1, 0), "First phase of cycle 1.");
+        assert_eq!(schedule.cycle_of_phase(7 * min - 1), (2, 4 * min - 1), "Last warm-up phase.");
+        assert_eq!(schedule.cycle_of_phase(7 * min), (3, 0), "First normal phase.");
+        assert_eq!(schedule.cycle_of_phase(7 * min + 256), (4, 0), "Second normal cycle.");
+        assert_eq!(schedule.first_phase_of_cycle(2), Some(3 * min), "Cycle 2 should start after cycles 0 and 1.");
+        assert_eq!(schedule.last_phase_of_cycle(4), Some(7 * min + 511), "Cycle 4 should end after two normal cycles.");
+    }
+
+    #[test]
+    fn warmup_with_uneven_cycle_length() {
+        let schedule: CycleSchedule = warmup(100);
+        assert_eq!(schedule.first_normal_cycle, 2, "Doubling to 128 would overshoot 100 phases.");
+        assert_eq!(schedule.first_normal_phase, 96, "Cycles of 32 and 64 phases should precede the normal ones.");
+
+        let short: CycleSchedule = warmup(DEFAULT_MINIMUM_PHASES_PER_CYCLE);
+        assert_eq!(short.first_normal_cycle, 0, "Cycles no longer than the minimum need no warm-up.");
+    }
+
+    #[test]
+    fn conversions_round_trip() {
+        for schedule in [warmup(256), warmup(100), ClockConfig::default().schedule()] {
+            for phase in 0..2_000 {
+                let (cycle, offset) = schedule.cycle_of_phase(phase);
+                assert!(offset < schedule.phases_in_cycle(cycle), "Offset should fall inside the cycle.");
+                assert_eq!(
+                    schedule.first_phase_of_cycle(cycle).map(|first| first + offset),
+                    Some(phase),
+                    "Phase should round-trip."
+                );
+            }
+            for cycle in 0..10 {
+                let first: u64 = schedule.first_rev_of_cycle(cycle).unwrap();
+                let last: u64 = schedule.last_rev_of_cycle(cycle).unwrap();
+                assert_eq!(schedule.cycle_of_rev(first), cycle, "First rev should belong to its cycle.");
+                assert_eq!(schedule.cycle_of_rev(last), cycle, "Last rev should belong to its cycle.");
+                assert_eq!(schedule.first_rev_of_cycle(cycle + 1), Some(last + 1), "Cycles should be contiguous.");
+            }
+        }
+    }
+
+    #[test]
+    fn wall_clock() {
+        let schedule: CycleSchedule = ClockConfig::default().schedule();
+
+        assert_eq!(schedule.us_at_rev(160), Some(160 * DEFAULT_US_PER_REV), "160 revs should take a second.");
+        assert_eq!(schedule.rev_at_us(1_000_000), 160, "Rev 160 should be in progress after a second.");
+        assert_eq!(schedule.rev_at_us(DEFAULT_US_PER_REV - 1), 0, "Rev 0 lasts one rev duration.");
+    }
+
+    #[test]
+    fn checked_arithmetic() {
+        let schedule: CycleSchedule = warmup(256);
+
+        assert_eq!(schedule.us_at_rev(u64::MAX), None, "Wall-clock overflow should be reported.");
+        assert_eq!(schedule.first_rev_of_phase(u64::MAX), None, "Rev overflow should be reported.");
+        assert_eq!(schedule.last_rev_of_cycle(u64::MAX), None, "Cycle overflow should be reported.");
+        assert_eq!(schedule.phases_in_cycle(u64::MAX), 256, "Far cycles should be full length.");
+    }
+}
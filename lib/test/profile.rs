@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod profiles {
+    use lib::{
+        metronome::{ClockConfig, DEFAULT_DEV_PHASES_PER_CYCLE},
+        profile::{Profile, ProfileConfig, ProfileError},
+    };
+
+    #[test]
+    fn names_round_trip() {
+        for profile in Profile::ALL {
+            assert_eq!(profile.name().parse::<Profile>(), Ok(profile), "Profile names should parse back.");
+        }
+        assert_eq!(" Development ".parse::<Profile>(), Ok(Profile::Development), "Names should parse case-insensitively.");
+        assert_eq!(
+            "staging".parse::<Profile>(),
+            Err(ProfileError::UnknownName("staging".to_string())),
+            "Unknown names should be rejected."
+        );
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(Profile::Production.clock(), Some(ClockConfig::default()), "Production should use the default clock.");
+        assert_eq!(
+            Profile::Development.clock().map(|clock| clock.phases_per_cycle),
+            Some(DEFAULT_DEV_PHASES_PER_CYCLE),
+            "Development should use short cycles."
+        );
+
+        let test: ClockConfig = Profile::Test.clock().unwrap();
+        assert!(!test.pace, "The test profile should not pace revs.");
+        assert!(test.hashes_per_rev < 100, "The test profile should hash very little per rev.");
+
+        for profile in Profile::ALL {
+            if let Some(clock) = profile.clock() {
+                assert_eq!(clock.validate(), Ok(()), "Preset clocks should be valid.");
+            }
+        }
+        assert_eq!(Profile::Custom.clock(), None, "Custom has no preset.");
+        assert_eq!(ProfileConfig::named(Profile::Custom), Err(ProfileError::MissingClock), "Custom needs a clock.");
+    }
+
+    #[test]
+    fn select_by_name() {
+        let config: ProfileConfig = "development".parse().unwrap();
+
+        assert_eq!(config.profile, Profile::Development, "Profile should be selected by name.");
+        assert_eq!(config.clock, Profile::Development.clock().unwrap(), "Clock should be the preset.");
+    }
+
+    #[test]
+    fn load_from_config_file() {
+        let named: ProfileConfig = serde_json::from_str(r#"{"profile": "test"}"#).unwrap();
+        assert_eq!(named, ProfileConfig::named(Profile::Test).unwrap(), "Named profiles should fill in their clock.");
+
+        let custom: ProfileConfig = serde_json::from_str(r#"{"profile": "custom", "clock": {"phases_per_cycle": 16}}"#).unwrap();
+        assert_eq!(custom.profile, Profile::Custom, "Custom profile should be kept.");
+        assert_eq!(custom.clock.phases_per_cycle, 16, "Custom clock should be loaded.");
+
+        assert!(
+            serde_json::from_str::<ProfileConfig>(r#"{"profile": "production", "clock": {"phases_per_cycle": 16}}"#).is_err(),
+            "Named profiles should not be overridden."
+        );
+        assert!(
+            serde_json::from_str::<ProfileConfig>(r#"{"profile": "custom"}"#).is_err(),
+            "Custom profiles need a clock."
+        );
+
+        let json: String = serde_json::to_string(&named).unwrap();
+        assert_eq!(serde_json::from_str::<ProfileConfig>(&json).unwrap(), named, "Config should round-trip through JSON.");
+    }
+}
@@ -3,10 +3,10 @@ use std::{
     time::{Duration, Instant},
 };
 
-use poh::types::{PoH, Record};
+use poh::types::{PoH, Record, RecordRef};
 
 use lib::{
-    hash::{Algorithm, Hasher},
+    hash::{Algorithm, ChainHasher, Hash, Hasher, Lanes, Segment},
     metronome::{DEFAULT_HASHES_PER_REV, DEFAULT_US_PER_REV},
 };
 
@@ -25,17 +25,34 @@ fn hash_operations(c: &mut Criterion) {
     });
     // Benchmark hash with data (event insertion).
     group.bench_function("embed_data", |b| {
-        let prev_hash: [u8; 32] = [1u8; 32];
+        let prev_hash: Hash = Hash::new([1u8; 32]);
         let data: &'static [u8; 38] = b"This is an event data for benchmarking";
         b.iter(|| hasher.embed_data(black_box(&prev_hash), black_box(data)))
     });
     // Benchmark extending hash chain with different iteration counts.
     for iterations in [100, 1000, DEFAULT_HASHES_PER_REV].iter() {
         group.bench_with_input(BenchmarkId::new("extend_hash_chain", iterations), iterations, |b, &iterations| {
-            let prev_hash: [u8; 32] = [2u8; 32];
+            let prev_hash: Hash = Hash::new([2u8; 32]);
             b.iter(|| hasher.extend_hash_chain(black_box(&prev_hash), black_box(iterations)))
         });
     }
+    // Benchmark batch verification of independent segments for every lane width.
+    let segments: Vec<Segment<'_>> = (0..64u8)
+        .map(|i| {
+            let start: Hash = Hash::new([i; 32]);
+            return Segment {
+                start,
+                end: hasher.extend_hash_chain(&start, 1000),
+                iterations: 1000,
+                event: None,
+            };
+        })
+        .collect();
+    for lanes in Lanes::ALL.iter() {
+        group.bench_with_input(BenchmarkId::new("verify_hash_chains", format!("{:?}", lanes)), lanes, |b, &lanes| {
+            b.iter(|| hasher.verify_hash_chains_with(black_box(&segments), black_box(lanes)))
+        });
+    }
     group.finish();
 }
 
@@ -74,15 +91,15 @@ fn verification(c: &mut Criterion) {
     group.measurement_time(Duration::from_secs(3));
     // Benchmark hash chain verification.
     group.bench_function("verify_hash_chain", |b| {
-        let prev_hash: [u8; 32] = [3u8; 32];
-        let extended: [u8; 32] = hasher.extend_hash_chain(&prev_hash, DEFAULT_HASHES_PER_REV);
+        let prev_hash: Hash = Hash::new([3u8; 32]);
+        let extended: Hash = hasher.extend_hash_chain(&prev_hash, DEFAULT_HASHES_PER_REV);
         b.iter(|| hasher.verify_hash_chain(black_box(&prev_hash), black_box(&extended), black_box(DEFAULT_HASHES_PER_REV), black_box(None)))
     });
     // Benchmark hash chain verification with event data.
     group.bench_function("verify_hash_chain_with_event", |b| {
-        let prev_hash: [u8; 32] = [4u8; 32];
+        let prev_hash: Hash = Hash::new([4u8; 32]);
         let event_data: &'static [u8; 37] = b"Event data for verification benchmark";
-        let mut hash: [u8; 32] = hasher.embed_data(&prev_hash, event_data);
+        let mut hash: Hash = hasher.embed_data(&prev_hash, event_data);
 
         hash = hasher.extend_hash_chain(&hash, DEFAULT_HASHES_PER_REV);
         b.iter(|| {
@@ -97,6 +114,31 @@ fn verification(c: &mut Criterion) {
     group.finish();
 }
 
+fn record_encoding(c: &mut Criterion) {
+    let mut group: BenchmarkGroup<'_, criterion::measurement::WallTime> = c.benchmark_group("Record Encoding");
+    let record: Record = Record {
+        hash: Hash::new([5u8; 32]),
+        rev_index: 1_000_000,
+        phase_index: 15_625,
+        cycle_index: 0,
+        timestamp_ms: 6_250_000,
+        num_hashes: Some(100),
+        events: vec![b"Event data for encoding benchmark".to_vec()],
+    };
+    let bytes: Vec<u8> = record.to_bytes().unwrap();
+    let json: String = serde_json::to_string(&record).unwrap();
+
+    group.warm_up_time(Duration::from_millis(500));
+    group.measurement_time(Duration::from_secs(2));
+    // Benchmark binary against JSON, in both directions.
+    group.bench_function("encode_binary", |b| b.iter(|| black_box(&record).to_bytes().unwrap()));
+    group.bench_function("encode_json", |b| b.iter(|| serde_json::to_string(black_box(&record)).unwrap()));
+    group.bench_function("decode_binary_in_place", |b| b.iter(|| RecordRef::decode(black_box(&bytes)).unwrap()));
+    group.bench_function("decode_binary", |b| b.iter(|| Record::decode(black_box(&bytes)).unwrap()));
+    group.bench_function("decode_json", |b| b.iter(|| serde_json::from_str::<Record>(black_box(&json)).unwrap()));
+    group.finish();
+}
+
 fn poh_generation(c: &mut Criterion) {
     let mut group: BenchmarkGroup<'_, criterion::measurement::WallTime> = c.benchmark_group("PoH Generation");
     group.warm_up_time(Duration::from_millis(1000));
@@ -188,11 +230,11 @@ fn realtime_performance(c: &mut Criterion) {
                 let elapsed_us: u64 = start.elapsed().as_micros() as u64;
 
                 if elapsed_us < next_rev_target_us {
-                    let sleep_us: u64 = next_rev_target_us - elapsed_us;
+                    let sleep_us: u64 = next_rev_target_us.saturating_sub(elapsed_us);
                     std::thread::sleep(Duration::from_micros(sleep_us));
                 }
 
-                total_duration += start.elapsed();
+                total_duration = total_duration.saturating_add(start.elapsed());
                 black_box(record);
             }
             total_duration
@@ -202,6 +244,6 @@ fn realtime_performance(c: &mut Criterion) {
 }
 
 criterion_group!(
-    benches, hash_operations, poh_core, verification, poh_generation, hash_algorithms, realtime_performance,
+    benches, hash_operations, poh_core, verification, record_encoding, poh_generation, hash_algorithms, realtime_performance,
 );
 criterion_main!(benches);
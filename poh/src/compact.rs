@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+
+use crate::{
+    record::Cursor,
+    types::{Anchor, CompactEntry, PoH, Record, TickRun, VerificationError, VerificationErrorKind, VerificationReport, VerifyMode},
+};
+
+use lib::{
+    hash::{ChainHasher, Hash},
+    metronome::{ClockConfig, CycleSchedule},
+};
+
+impl PoH {
+    /// Collapses runs of plain ticks, whole-rev records without events, into [`TickRun`]s.
+    ///
+    /// The first record is always kept, so every run has an entry before it to hash from. A tick only joins a run
+    /// when its indices follow the run under `config` and its timestamp keeps the run on one `us_per_rev` schedule,
+    /// so the compaction is lossless for any ledger that verifies.
+    pub fn compact_records(config: &ClockConfig, records: &[Record]) -> Vec<CompactEntry> {
+        let schedule: CycleSchedule = config.schedule();
+        let mut entries: Vec<CompactEntry> = Vec::new();
+        let mut first: bool = true;
+        // Latest start time that still fits every tick of the last run, `start_us` being the earliest.
+        let mut latest_start_us: u64 = 0;
+
+        for record in records {
+            let tick: bool = !first && record.events.is_empty() && record.num_hashes.is_none() && record.tick_indices(&schedule);
+            let joined: bool = tick
+                && match entries.last_mut() {
+                    Some(CompactEntry::Ticks(run)) => run.push(record, &mut latest_start_us, config.us_per_rev),
+                    _ => false,
+                };
+
+            if !joined {
+                entries.push(match tick {
+                    true => {
+                        let start_us: u64 = record.timestamp_ms.saturating_mul(1_000);
+                        latest_start_us = start_us.saturating_add(999);
+                        CompactEntry::Ticks(TickRun {
+                            start_rev: record.rev_index,
+                            count: 1,
+                            hash: record.hash,
+                            start_us,
+                        })
+                    }
+                    false => CompactEntry::Record(record.clone()),
+                });
+            }
+            first = false;
+        }
+        return entries;
+    }
+}
+
+impl<H: ChainHasher> PoH<H> {
+    /// Expands compacted entries back into the full record sequence, re-hashing every run from the entry before it.
+    ///
+    /// A leading run hashes from `anchor`, and fails without one.
+    pub fn expand_records(hasher: &H, config: &ClockConfig, anchor: Option<&Anchor>, entries: &[CompactEntry]) -> Result<Vec<Record>, VerificationError> {
+        let schedule: CycleSchedule = config.schedule();
+        let mut records: Vec<Record> = Vec::new();
+        let mut prev: Option<Hash> = anchor.map(|anchor| anchor.hash);
+
+        for (index, entry) in entries.iter().enumerate() {
+            match entry {
+                CompactEntry::Record(record) => records.push(record.clone()),
+                CompactEntry::Ticks(run) => {
+                    let mut hash: Hash = prev.ok_or(VerificationError {
+                        index: Some(index),
+                        kind: VerificationErrorKind::UnanchoredRun,
+                    })?;
+                    for offset in 0..run.count {
+                        let last: bool = offset.saturating_add(1) == run.count;
+                        // The stored hash stands for the last tick, so a forged run still expands to what it claims.
+                        hash = if last { run.hash } else { hasher.extend_hash_chain(&hash, config.hashes_per_rev) };
+                        records.push(run.tick(offset, hash, run.timestamp_ms(offset, config.us_per_rev), &schedule));
+                    }
+                }
+            }
+            prev = records.last().map(|record| record.hash);
+        }
+        return Ok(records);
+    }
+
+    pub fn verify_compact(hasher: &H, config: &ClockConfig, entries: &[CompactEntry]) -> Result<(), VerificationError> {
+        return Self::compact_report(hasher, config, None, entries, VerifyMode::StopAtFirst).into_result();
+    }
+
+    /// Like [`PoH::anchored_report`] over compacted entries, checking each run with a single chain extension
+    /// instead of expanding it. Failures are reported by entry index.
+    pub fn compact_report(hasher: &H, config: &ClockConfig, anchor: Option<&Anchor>, entries: &[CompactEntry], mode: VerifyMode) -> VerificationReport {
+        let mut report: VerificationReport = VerificationReport::new(entries.len());
+        let schedule: CycleSchedule = config.schedule();
+        let mut cursor: Option<Cursor> = anchor.map(Cursor::at);
+
+        for (index, entry) in entries.iter().enumerate() {
+            let run: &TickRun = match entry {
+                CompactEntry::Record(record) => {
+                    if cursor.is_some_and(|from| !report.check_record(hasher, config, &from, index, record, mode)) {
+                        return report;
+                    }
+                    cursor = Some(Cursor::after(cursor.as_ref(), record, config.hashes_per_rev));
+                    continue;
+                }
+                CompactEntry::Ticks(run) => run,
+            };
+
+            let last: Record = run.tick(run.count.saturating_sub(1), run.hash, 0, &schedule);
+            match &cursor {
+                Some(from) => {
+                    let first: Record = run.tick(0, run.hash, 0, &schedule);
+                    for kind in from.errors(&first, config.hashes_per_rev, &schedule) {
+                        if !report.push(index, kind, mode) {
+                            return report;
+                        }
+                    }
+
+                    let expected: Hash = hasher.extend_hash_chain(&from.hash, run.count.saturating_mul(config.hashes_per_rev));
+                    if expected != run.hash && !report.push(index, VerificationErrorKind::HashMismatch { expected, actual: run.hash }, mode) {
+                        return report;
+                    }
+                }
+                None => {
+                    if !report.push(index, VerificationErrorKind::UnanchoredRun, mode) {
+                        return report;
+                    }
+                }
+            }
+            cursor = Some(Cursor::at(&Anchor::after(&last)));
+        }
+        return report;
+    }
+}
+
+impl TickRun {
+    /// The rev right after the run.
+    pub fn next_rev(&self) -> u64 {
+        return self.start_rev.saturating_add(self.count);
+    }
+
+    /// Timestamp of the tick `offset` revs into the run, in milliseconds.
+    pub fn timestamp_ms(&self, offset: u64, us_per_rev: u64) -> u64 {
+        return self.start_us.saturating_add(offset.saturating_mul(us_per_rev)) / 1_000;
+    }
+
+    // Extends the run with the next tick, if it follows on and some start time between `start_us` and `latest_start_us`
+    // puts every tick, this one included, at its timestamp. Narrows that window to the start times that still do.
+    fn push(&mut self, record: &Record, latest_start_us: &mut u64, us_per_rev: u64) -> bool {
+        if record.rev_index != self.next_rev() {
+            return false;
+        }
+        let elapsed_us: u64 = self.count.saturating_mul(us_per_rev);
+        let latest_us: Option<u64> = record.timestamp_ms.saturating_mul(1_000).saturating_add(999).checked_sub(elapsed_us);
+        let start_us: u64 = self.start_us.max(record.timestamp_ms.saturating_mul(1_000).saturating_sub(elapsed_us));
+        return match latest_us.map(|latest_us| latest_us.min(*latest_start_us)) {
+            Some(latest_us) if start_us <= latest_us => {
+                self.count = self.count.saturating_add(1);
+                self.hash = record.hash;
+                self.start_us = start_us;
+                *latest_start_us = latest_us;
+                true
+            }
+            _ => false,
+        };
+    }
+
+    // The tick `offset` revs into the run.
+    fn tick(&self, offset: u64, hash: Hash, timestamp_ms: u64, schedule: &CycleSchedule) -> Record {
+        let rev_index: u64 = self.start_rev.saturating_add(offset);
+        return Record {
+            hash,
+            rev_index,
+            phase_index: schedule.phase_of_rev(rev_index),
+            cycle_index: schedule.cycle_of_rev(rev_index),
+            timestamp_ms,
+            num_hashes: None,
+            events: Vec::new(),
+        };
+    }
+}
+
+impl Record {
+    // Whether the phase and cycle indices are the ones the schedule derives from the rev index.
+    fn tick_indices(&self, schedule: &CycleSchedule) -> bool {
+        return self.phase_index == schedule.phase_of_rev(self.rev_index) && self.cycle_index == schedule.cycle_of_rev(self.rev_index);
+    }
+}
@@ -0,0 +1,192 @@
+use alloc::vec::Vec;
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::types::{DecodeError, EncodeError, Events, RECORD_HEADER_BYTES, RECORD_VERSION, Record, RecordRef};
+
+use lib::hash::{HASH_BYTES, Hash};
+
+// Set when `num_hashes` is present.
+const FLAG_NUM_HASHES: u8 = 1;
+
+impl Record {
+    pub fn encoded_len(&self) -> usize {
+        return self
+            .events
+            .iter()
+            .fold(RECORD_HEADER_BYTES, |len, event| len.saturating_add(4).saturating_add(event.len()));
+    }
+
+    /// Appends the binary encoding to `out`, leaving it untouched if the record does not fit the layout.
+    pub fn encode(&self, out: &mut Vec<u8>) -> Result<(), EncodeError> {
+        let count: u32 = u32::try_from(self.events.len()).map_err(|_| EncodeError::TooManyEvents(self.events.len()))?;
+        if let Some((index, event)) = self.events.iter().enumerate().find(|(_, event)| u32::try_from(event.len()).is_err()) {
+            return Err(EncodeError::EventTooLarge { index, len: event.len() });
+        }
+
+        out.reserve(self.encoded_len());
+        out.push(RECORD_VERSION);
+        out.push(if self.num_hashes.is_some() { FLAG_NUM_HASHES } else { 0 });
+        out.extend_from_slice(self.hash.as_bytes());
+        for value in [self.rev_index, self.phase_index, self.cycle_index, self.timestamp_ms, self.num_hashes.unwrap_or(0)] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+
+        out.extend_from_slice(&count.to_le_bytes());
+        for event in &self.events {
+            // Lengths were checked above.
+            out.extend_from_slice(&(event.len() as u32).to_le_bytes());
+            out.extend_from_slice(event);
+        }
+        return Ok(());
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, EncodeError> {
+        let mut out: Vec<u8> = Vec::with_capacity(self.encoded_len());
+        self.encode(&mut out)?;
+        return Ok(out);
+    }
+
+    /// Decodes the record at the start of `bytes`, returning it and the bytes it took.
+    pub fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (record, len): (RecordRef<'_>, usize) = RecordRef::decode(bytes)?;
+        return Ok((record.to_record(), len));
+    }
+}
+
+impl<'a> RecordRef<'a> {
+    /// Decodes the record at the start of `bytes` without copying its events, returning it and the bytes it took.
+    pub fn decode(bytes: &'a [u8]) -> Result<(Self, usize), DecodeError> {
+        let mut reader: Reader<'a> = Reader { bytes, offset: 0 };
+
+        let version: u8 = reader.take::<1>()?[0];
+        if version != RECORD_VERSION {
+            return Err(DecodeError::UnknownVersion(version));
+        }
+        let flags: u8 = reader.take::<1>()?[0];
+        if flags & !FLAG_NUM_HASHES != 0 {
+            return Err(DecodeError::UnknownFlags(flags));
+        }
+
+        let hash: Hash = Hash::new(reader.take::<HASH_BYTES>()?);
+        let rev_index: u64 = reader.u64()?;
+        let phase_index: u64 = reader.u64()?;
+        let cycle_index: u64 = reader.u64()?;
+        let timestamp_ms: u64 = reader.u64()?;
+        let num_hashes: u64 = reader.u64()?;
+        if flags & FLAG_NUM_HASHES == 0 && num_hashes != 0 {
+            return Err(DecodeError::UnflaggedHashCount(num_hashes));
+        }
+        let count: u32 = reader.u32()?;
+
+        // Check every length now, so iterating the events cannot fail.
+        let events_start: usize = reader.offset;
+        for _ in 0..count {
+            let len: u32 = reader.u32()?;
+            reader.slice(len as usize)?;
+        }
+
+        let record: Self = Self {
+            hash,
+            rev_index,
+            phase_index,
+            cycle_index,
+            timestamp_ms,
+            num_hashes: if flags & FLAG_NUM_HASHES != 0 { Some(num_hashes) } else { None },
+            events: Events {
+                bytes: &bytes[events_start..reader.offset],
+                remaining: count,
+            },
+        };
+        return Ok((record, reader.offset));
+    }
+
+    pub fn to_record(&self) -> Record {
+        return Record {
+            hash: self.hash,
+            rev_index: self.rev_index,
+            phase_index: self.phase_index,
+            cycle_index: self.cycle_index,
+            timestamp_ms: self.timestamp_ms,
+            num_hashes: self.num_hashes,
+            events: self.events.clone().map(<[u8]>::to_vec).collect(),
+        };
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        let (len, rest): (&[u8], &'a [u8]) = self.bytes.split_at(4);
+        let len: usize = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        let (event, rest): (&'a [u8], &'a [u8]) = rest.split_at(len);
+        self.bytes = rest;
+        return Some(event);
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return (self.remaining as usize, Some(self.remaining as usize));
+    }
+}
+
+impl ExactSizeIterator for Events<'_> {}
+
+// Bounds-checked cursor over the input.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let needed: usize = self.offset.saturating_add(len);
+        let slice: &'a [u8] = self.bytes.get(self.offset..needed).ok_or(DecodeError::Truncated {
+            needed,
+            available: self.bytes.len(),
+        })?;
+        self.offset = needed;
+        return Ok(slice);
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], DecodeError> {
+        let mut array: [u8; N] = [0u8; N];
+        array.copy_from_slice(self.slice(N)?);
+        return Ok(array);
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        return Ok(u32::from_le_bytes(self.take::<4>()?));
+    }
+
+    fn u64(&mut self) -> Result<u64, DecodeError> {
+        return Ok(u64::from_le_bytes(self.take::<8>()?));
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            DecodeError::Truncated { needed, available } => write!(f, "Record needs {} bytes, only {} available.", needed, available),
+            DecodeError::UnknownVersion(version) => write!(f, "Unknown record version {}.", version),
+            DecodeError::UnknownFlags(flags) => write!(f, "Unknown record flags {:#04x}.", flags),
+            DecodeError::UnflaggedHashCount(num_hashes) => write!(f, "Hash count {} is present without its flag.", num_hashes),
+        };
+    }
+}
+
+impl Error for DecodeError {}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            EncodeError::TooManyEvents(count) => write!(f, "A record cannot encode {} events.", count),
+            EncodeError::EventTooLarge { index, len } => write!(f, "Event {} of {} bytes is too large to encode.", index, len),
+        };
+    }
+}
+
+impl Error for EncodeError {}
@@ -0,0 +1,54 @@
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result},
+};
+
+use crate::types::{LedgerError, LedgerMeta};
+
+use lib::{hash::Hasher, profile::ProfileConfig};
+
+impl LedgerMeta {
+    /// The hasher the ledger was written with.
+    pub fn hasher(&self) -> Hasher {
+        return Hasher::with_mode(self.algorithm, self.mode);
+    }
+
+    /// Checks that the ledger can be verified with `hasher` under the `expected` profile.
+    pub fn check(&self, hasher: &Hasher, expected: &ProfileConfig) -> core::result::Result<(), LedgerError> {
+        if self.profile.profile != expected.profile {
+            return Err(LedgerError::ProfileMismatch {
+                expected: expected.profile,
+                actual: self.profile.profile,
+            });
+        }
+        if self.profile.clock != expected.clock {
+            return Err(LedgerError::ClockMismatch);
+        }
+        if hasher.algorithm() != self.algorithm {
+            return Err(LedgerError::AlgorithmMismatch {
+                expected: hasher.algorithm(),
+                actual: self.algorithm,
+            });
+        }
+        if hasher.mode() != self.mode {
+            return Err(LedgerError::ModeMismatch {
+                expected: hasher.mode(),
+                actual: self.mode,
+            });
+        }
+        return Ok(());
+    }
+}
+
+impl Display for LedgerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        return match self {
+            LedgerError::ProfileMismatch { expected, actual } => write!(f, "Ledger was produced under the '{}' profile, expected '{}'.", actual, expected),
+            LedgerError::ClockMismatch => write!(f, "Ledger clock config does not match the expected one."),
+            LedgerError::AlgorithmMismatch { expected, actual } => write!(f, "Ledger was hashed with {}, verifier uses {}.", actual, expected),
+            LedgerError::ModeMismatch { expected, actual } => write!(f, "Ledger was hashed in mode {:?}, verifier uses {:?}.", actual, expected),
+        };
+    }
+}
+
+impl Error for LedgerError {}
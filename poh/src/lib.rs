@@ -1,5 +1,18 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod compact;
+mod encoding;
+mod ledger;
 mod poh;
 mod record;
-mod serializer;
+mod sample;
+#[cfg(feature = "std")]
+mod service;
+mod snapshot;
+#[cfg(feature = "std")]
+mod store;
+mod verify;
 
 pub mod types;
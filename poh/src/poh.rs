@@ -1,167 +1,209 @@
-use std::{
-    thread as std_thread,
-    time::{Duration, Instant},
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
 };
 
-use crate::types::{PoH, Record};
+use crate::types::{Anchor, LedgerMeta, PoH, Record, ResumeError};
 
 use lib::{
-    hash::Hasher,
-    metronome::{DEFAULT_HASHES_PER_REV, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE, DEFAULT_SPINLOCK_THRESHOLD_US, DEFAULT_US_PER_REV},
+    clock::Clock,
+    hash::{ChainHasher, Hasher},
+    metronome::{ClockConfig, ClockConfigError, CycleSchedule},
+    profile::ProfileConfig,
+};
+#[cfg(feature = "std")]
+use lib::{
+    clock::{MonotonicClock, unix_time_us},
+    hash::Algorithm,
+    profile::Profile,
 };
 
+#[cfg(feature = "std")]
 impl PoH {
     pub fn new(seed: &[u8]) -> Self {
-        let hasher: Hasher = Hasher::default();
-        let current_hash: [u8; 32] = hasher.hash(seed);
-        return Self {
-            current_hash,
-            rev_count: 0,
-            phase_count: 0,
-            cycle_count: 0,
-            start_time: Instant::now(),
-            next_rev_target_us: DEFAULT_US_PER_REV,
+        return Self::with_hasher(seed, Hasher::default());
+    }
+
+    pub fn with_algorithm(seed: &[u8], algorithm: Algorithm) -> Self {
+        return Self::with_hasher(seed, Hasher::new(algorithm));
+    }
+}
+
+impl<C: Clock> PoH<Hasher, C> {
+    /// Metadata to store with the records this instance produces.
+    pub fn ledger_meta(&self) -> LedgerMeta {
+        return LedgerMeta {
+            profile: ProfileConfig {
+                profile: self.profile,
+                clock: self.config,
+            },
+            algorithm: self.hasher.algorithm(),
+            mode: self.hasher.mode(),
+            genesis_unix_us: self.epoch_unix_us.map(|_| self.genesis_us),
         };
     }
+}
 
-    pub fn next_rev(&mut self) -> Record {
-        return self.core(None);
+// Real-time generators stamp records with UNIX time.
+#[cfg(feature = "std")]
+impl<H: ChainHasher> PoH<H> {
+    pub fn with_hasher(seed: &[u8], hasher: H) -> Self {
+        return Self::build(seed, hasher, ProfileConfig::default(), MonotonicClock::new()).at_unix_time(unix_time_us());
     }
 
-    pub fn insert_event(&mut self, event_data: &[u8]) -> Record {
-        return self.core(Some(event_data));
+    /// Generates under a custom clock, recorded as the `Custom` profile.
+    pub fn with_config(seed: &[u8], hasher: H, config: ClockConfig) -> Result<Self, ClockConfigError> {
+        let profile: ProfileConfig = ProfileConfig {
+            profile: Profile::Custom,
+            clock: config,
+        };
+        return Self::with_profile(seed, hasher, &profile);
     }
 
-    pub fn verify_records(records: &[Record]) -> bool {
-        if records.is_empty() {
-            return false;
+    pub fn with_profile(seed: &[u8], hasher: H, profile: &ProfileConfig) -> Result<Self, ClockConfigError> {
+        return Ok(Self::with_clock(seed, hasher, profile, MonotonicClock::new())?.at_unix_time(unix_time_us()));
+    }
+}
+
+impl<H: ChainHasher, C: Clock> PoH<H, C> {
+    /// Generates with an injected time source, such as `ManualClock` or `FastForwardClock` in tests.
+    pub fn with_clock(seed: &[u8], hasher: H, profile: &ProfileConfig, clock: C) -> Result<Self, ClockConfigError> {
+        profile.clock.validate()?;
+        return Ok(Self::build(seed, hasher, *profile, clock));
+    }
+
+    /// Continues a chain from `anchor`, with `rev_hashes` hashes already spent by entries of the rev at `anchor.next_rev`.
+    pub fn resume(hasher: H, profile: &ProfileConfig, clock: C, anchor: &Anchor, rev_hashes: u64) -> Result<Self, ResumeError> {
+        profile.clock.validate()?;
+        if rev_hashes >= profile.clock.hashes_per_rev {
+            return Err(ResumeError::RevHashes {
+                rev_hashes,
+                hashes_per_rev: profile.clock.hashes_per_rev,
+            });
         }
+        return Ok(Self::at(hasher, *profile, clock, anchor, rev_hashes));
+    }
 
-        let hasher: Hasher = Hasher::default();
+    /// Anchors the clock to wall time, `unix_us` being the UNIX time in microseconds at its current reading,
+    /// so records carry UNIX timestamps. The genesis time moves along with them.
+    pub fn at_unix_time(mut self, unix_us: u64) -> Self {
+        let epoch_unix_us: u64 = unix_us.saturating_sub(self.clock.now_us());
+        self.genesis_us = self.genesis_us.saturating_sub(self.epoch_unix_us.unwrap_or(0)).saturating_add(epoch_unix_us);
+        self.epoch_unix_us = Some(epoch_unix_us);
+        return self;
+    }
 
-        for window in records.windows(2) {
-            let prev: &Record = &window[0];
-            let curr: &Record = &window[1];
-            let event_data: Option<&[u8]> = curr.event.as_deref();
+    fn build(seed: &[u8], hasher: H, profile: ProfileConfig, clock: C) -> Self {
+        let genesis: Anchor = Anchor::genesis(&hasher, seed);
+        return Self::at(hasher, profile, clock, &genesis, 0);
+    }
 
-            if !hasher.verify_hash_chain(&prev.hash, &curr.hash, DEFAULT_HASHES_PER_REV, event_data) {
-                return false;
-            }
+    fn at(hasher: H, profile: ProfileConfig, clock: C, anchor: &Anchor, rev_hashes: u64) -> Self {
+        let config: ClockConfig = profile.clock;
+        let schedule: CycleSchedule = config.schedule();
+        let (cycle_count, phase_count): (u64, u64) = schedule.cycle_of_phase(schedule.phase_of_rev(anchor.next_rev));
+        let now_us: u64 = clock.now_us();
+        let next_rev_target_us: u64 = now_us.saturating_add(config.us_per_rev);
+        return Self {
+            hasher,
+            profile: profile.profile,
+            config,
+            current_hash: anchor.hash,
+            rev_count: anchor.next_rev,
+            phase_count,
+            cycle_count,
+            rev_hashes,
+            clock,
+            next_rev_target_us,
+            epoch_unix_us: None,
+            // As if every rev before the anchor had been on schedule.
+            genesis_us: now_us.saturating_sub(anchor.next_rev.saturating_mul(config.us_per_rev)),
+        };
+    }
 
-            // Verify sequence numbers.
-            let rev_index_valid: bool = curr.rev_index == prev.rev_index.saturating_add(1);
-            let phase_index_valid: bool = curr.phase_index == curr.rev_index / DEFAULT_REVS_PER_PHASE;
-            let cycle_valid: bool = curr.cycle_index == curr.rev_index / (DEFAULT_REVS_PER_PHASE * DEFAULT_PHASES_PER_CYCLE);
+    /// Ends the current rev with a tick entry.
+    pub fn next_rev(&mut self) -> Record {
+        return self.core(&[], self.remaining_hashes());
+    }
 
-            if !(rev_index_valid && phase_index_valid && cycle_valid) {
-                return false;
-            }
-        }
-        return true;
+    /// Ends the current rev with an entry carrying `event_data`.
+    pub fn insert_event(&mut self, event_data: &[u8]) -> Record {
+        return self.core(&[event_data], self.remaining_hashes());
     }
 
-    pub fn verify_timestamps(records: &[Record], log_failures: bool) -> bool {
-        if records.is_empty() {
-            return false;
-        }
+    /// Records `events` in one entry a single hash after the previous one, without waiting for the rev to end.
+    /// The entry only ends the rev if that hash is the rev's last.
+    pub fn insert_events(&mut self, events: &[&[u8]]) -> Record {
+        return self.core(events, 1);
+    }
 
-        let first_timestamp: u64 = records[0].timestamp_ms;
-
-        for (i, record) in records.iter().enumerate() {
-            let timestamp: u64 = record.timestamp_ms;
-            let expected_timestamp: u64 = first_timestamp.saturating_add((i as u64).checked_mul(DEFAULT_US_PER_REV).unwrap_or(0) / 1000);
-            // Adjust tolerance based on whether this is an event rev.
-            let allowed_drift: u64 = 8; // ~8ms tolerance, relaxed.
-            // Ensure we don't underflow.
-            let lower_bound: u64 = expected_timestamp.saturating_sub(allowed_drift);
-            let upper_bound: u64 = expected_timestamp.saturating_add(allowed_drift);
-
-            let too_early: bool = timestamp < lower_bound;
-            let too_late: bool = timestamp > upper_bound;
-
-            if too_early || too_late {
-                if log_failures {
-                    println!(
-                        "Timestamp mismatch at record {}: actual={}, expected={}, drift={}, allowed=~{}",
-                        i,
-                        timestamp,
-                        expected_timestamp,
-                        if too_early {
-                            lower_bound.saturating_sub(timestamp)
-                        } else {
-                            timestamp.saturating_sub(upper_bound)
-                        },
-                        allowed_drift
-                    );
-                }
-                return false;
-            }
-        }
-        return true;
+    // Hashes left before the current rev ends.
+    fn remaining_hashes(&self) -> u64 {
+        return self.config.hashes_per_rev.saturating_sub(self.rev_hashes);
     }
 
-    fn core(&mut self, event_data: Option<&[u8]>) -> Record {
-        // Control timing.
-        self.enforce_timing();
+    fn core(&mut self, events: &[&[u8]], num_hashes: u64) -> Record {
+        let ends_rev: bool = num_hashes >= self.remaining_hashes();
 
-        let hasher: Hasher = Hasher::default();
+        // Control timing, only the entry ending a rev waits for it. Unpaced, virtual time still moves to the rev's end.
+        if ends_rev {
+            self.clock.wait_until(self.next_rev_target_us, self.config.spinlock_threshold_us, self.config.pace);
+        }
 
-        if let Some(event) = event_data {
-            self.current_hash = hasher.embed_data(&self.current_hash, event);
+        for event in events {
+            self.current_hash = self.hasher.embed_data(&self.current_hash, event);
         }
 
-        self.current_hash = hasher.extend_hash_chain(&self.current_hash, DEFAULT_HASHES_PER_REV);
+        self.current_hash = self.hasher.extend_hash_chain(&self.current_hash, num_hashes);
 
+        let schedule: CycleSchedule = self.config.schedule();
         let rev_index: u64 = self.rev_count;
-        let phase_index: u64 = rev_index / DEFAULT_REVS_PER_PHASE;
-        let cycle_index: u64 = phase_index / DEFAULT_PHASES_PER_CYCLE;
+        let phase_index: u64 = schedule.phase_of_rev(rev_index);
+        let cycle_index: u64 = schedule.cycle_of_rev(rev_index);
         let record: Record = Record {
             hash: self.current_hash,
             rev_index,
             phase_index,
             cycle_index,
-            timestamp_ms: self.start_time.elapsed().as_millis() as u64,
-            event: event_data.map(|d| d.to_vec()),
+            timestamp_ms: self.epoch_unix_us.unwrap_or(0).saturating_add(self.clock.now_us()) / 1_000,
+            // Whole-rev entries keep the count implicit.
+            num_hashes: if self.rev_hashes == 0 && ends_rev { None } else { Some(num_hashes) },
+            events: events.iter().map(|event| event.to_vec()).collect(),
         };
 
-        self.rev_count = self.rev_count.checked_add(1).expect("rev_count overflow");
-
-        if self.rev_count % DEFAULT_REVS_PER_PHASE == 0 {
-            self.phase_count = self.phase_count.checked_add(1).expect("phase_count overflow");
+        if !ends_rev {
+            self.rev_hashes = self.rev_hashes.saturating_add(num_hashes);
+            return record;
         }
 
-        if phase_index % DEFAULT_PHASES_PER_CYCLE == 0 && self.rev_count % DEFAULT_REVS_PER_PHASE == 0 {
-            self.cycle_count = cycle_index;
-            self.phase_count = 0;
-        }
+        self.rev_hashes = 0;
+        self.rev_count = self.rev_count.checked_add(1).expect("rev_count overflow");
+
+        // Track the cycle of the next rev, and its phase within that cycle.
+        (self.cycle_count, self.phase_count) = schedule.cycle_of_phase(schedule.phase_of_rev(self.rev_count));
 
         // Calculate next rev target time.
-        self.next_rev_target_us = self.next_rev_target_us.saturating_add(DEFAULT_US_PER_REV);
+        self.next_rev_target_us = self.next_rev_target_us.saturating_add(self.config.us_per_rev);
 
         return record;
     }
+}
 
-    fn enforce_timing(&self) {
-        let elapsed_us: u64 = self.start_time.elapsed().as_micros() as u64;
-        let target_us: u64 = self.next_rev_target_us;
-
-        if elapsed_us < target_us {
-            let sleep_us: u64 = target_us.saturating_sub(elapsed_us);
-            // Use spin waiting for very short sleeps to improve precision.
-            if sleep_us < DEFAULT_SPINLOCK_THRESHOLD_US {
-                // Spin wait for greater timing precision.
-                let spin_until: u128 = self.start_time.elapsed().as_micros().saturating_add(sleep_us as u128);
-                while self.start_time.elapsed().as_micros() < spin_until {
-                    // Insert a pause instruction to reduce CPU usage during spin-waiting.
-                    #[cfg(target_arch = "x86_64")]
-                    unsafe {
-                        std::arch::x86_64::_mm_pause();
-                    }
-                }
-            } else {
-                // Use normal sleep for longer durations.
-                std_thread::sleep(Duration::from_micros(sleep_us));
+impl From<ClockConfigError> for ResumeError {
+    fn from(error: ClockConfigError) -> Self {
+        return ResumeError::Clock(error);
+    }
+}
+
+impl Display for ResumeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            ResumeError::Clock(error) => write!(f, "Cannot resume under an invalid clock config: {}", error),
+            ResumeError::RevHashes { rev_hashes, hashes_per_rev } => {
+                write!(f, "Cannot resume with {} hashes already spent in a rev of {}.", rev_hashes, hashes_per_rev)
             }
-        }
+        };
     }
 }
+
+impl Error for ResumeError {}
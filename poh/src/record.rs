@@ -1,14 +1,70 @@
-use std::fmt::{Display, Formatter, Result};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt::{Display, Formatter, Result};
 
-use crate::types::Record;
+use crate::types::{Anchor, Record, VerificationErrorKind};
 
-use hex::encode;
+use lib::{
+    hash::{ChainHasher, Hash},
+    metronome::CycleSchedule,
+};
+
+use serde::Deserialize;
+
+// Where the next entry is expected: the rev it belongs to, and the hashes already spent in that rev.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Cursor {
+    pub(crate) hash: Hash,
+    next_rev: u64,
+    // `None` after an unanchored first entry that may or may not have ended its rev.
+    spent: Option<u64>,
+}
+
+// Deserialization target, so records written with a single `event` before entries carried several still parse.
+#[derive(Deserialize)]
+pub(crate) struct RecordFields {
+    hash: Hash,
+    rev_index: u64,
+    phase_index: u64,
+    cycle_index: u64,
+    timestamp_ms: u64,
+    #[serde(default)]
+    num_hashes: Option<u64>,
+    #[serde(default)]
+    events: Option<Vec<Vec<u8>>>,
+    #[serde(default)]
+    event: Option<Vec<u8>>,
+}
+
+impl TryFrom<RecordFields> for Record {
+    type Error = &'static str;
+
+    fn try_from(fields: RecordFields) -> core::result::Result<Self, Self::Error> {
+        let events: Vec<Vec<u8>> = match (fields.events, fields.event) {
+            (Some(_), Some(_)) => return Err("a record cannot carry both `events` and the legacy `event`"),
+            (Some(events), None) => events,
+            (None, event) => event.into_iter().collect(),
+        };
+        return Ok(Self {
+            hash: fields.hash,
+            rev_index: fields.rev_index,
+            phase_index: fields.phase_index,
+            cycle_index: fields.cycle_index,
+            timestamp_ms: fields.timestamp_ms,
+            num_hashes: fields.num_hashes,
+            events,
+        });
+    }
+}
 
 impl Display for Record {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        let _event_desc: String = match &self.event {
-            Some(data) => format!("Event {} bytes", data.len()),
-            None => "No Event".to_string(),
+        let _event_desc: String = match self.events.len() {
+            0 => "No Event".to_string(),
+            count => format!("{} Events", count),
         };
         return write!(
             f,
@@ -17,7 +73,117 @@ impl Display for Record {
             self.phase_index,
             self.rev_index,
             self.timestamp_ms,
-            &encode(self.hash)[..17]
+            &self.hash.to_hex()[..17]
         );
     }
 }
+
+impl Record {
+    /// Hashes spent since the previous entry, given the clock's hashes per rev.
+    pub fn hash_count(&self, hashes_per_rev: u64) -> u64 {
+        return self.num_hashes.unwrap_or(hashes_per_rev);
+    }
+
+    /// The hash this entry should have, following `prev`.
+    pub fn expected_hash<H: ChainHasher>(&self, hasher: &H, prev: &Hash, hashes_per_rev: u64) -> Hash {
+        return hasher.extend_hash_chain(&self.mix_events(hasher, prev), self.hash_count(hashes_per_rev));
+    }
+
+    // The chain hash after mixing this entry's events into `prev`, in order.
+    pub(crate) fn mix_events<H: ChainHasher>(&self, hasher: &H, prev: &Hash) -> Hash {
+        return self.events.iter().fold(*prev, |hash, event| hasher.embed_data(&hash, event));
+    }
+
+    // Index mismatches against the expected rev, with phase and cycle checked against this record's own rev index.
+    pub(crate) fn index_errors(&self, expected_rev: u64, schedule: &CycleSchedule) -> Vec<VerificationErrorKind> {
+        let mut errors: Vec<VerificationErrorKind> = Vec::new();
+        let expected_phase: u64 = schedule.phase_of_rev(self.rev_index);
+        let expected_cycle: u64 = schedule.cycle_of_rev(self.rev_index);
+
+        if self.rev_index != expected_rev {
+            errors.push(VerificationErrorKind::RevGap {
+                expected: expected_rev,
+                actual: self.rev_index,
+            });
+        }
+        if self.phase_index != expected_phase {
+            errors.push(VerificationErrorKind::PhaseIndex {
+                expected: expected_phase,
+                actual: self.phase_index,
+            });
+        }
+        if self.cycle_index != expected_cycle {
+            errors.push(VerificationErrorKind::CycleIndex {
+                expected: expected_cycle,
+                actual: self.cycle_index,
+            });
+        }
+        return errors;
+    }
+}
+
+impl Cursor {
+    pub(crate) fn at(anchor: &Anchor) -> Self {
+        return Self {
+            hash: anchor.hash,
+            next_rev: anchor.next_rev,
+            spent: Some(0),
+        };
+    }
+
+    // Position after `record`, which followed `prev`, or was the unanchored first entry.
+    pub(crate) fn after(prev: Option<&Self>, record: &Record, hashes_per_rev: u64) -> Self {
+        let num_hashes: u64 = record.hash_count(hashes_per_rev);
+        let spent: Option<u64> = match prev {
+            Some(prev) => prev.position(record.rev_index).1,
+            // Only a whole-rev entry is known to start its rev.
+            None if num_hashes == hashes_per_rev => Some(0),
+            None => None,
+        };
+
+        return match spent.map(|spent| spent.saturating_add(num_hashes)) {
+            Some(spent) if spent < hashes_per_rev => Self {
+                hash: record.hash,
+                next_rev: record.rev_index,
+                spent: Some(spent),
+            },
+            Some(_) => Self {
+                hash: record.hash,
+                next_rev: record.rev_index.saturating_add(1),
+                spent: Some(0),
+            },
+            None => Self {
+                hash: record.hash,
+                next_rev: record.rev_index,
+                spent: None,
+            },
+        };
+    }
+
+    // Index, phase, cycle and hash count mismatches of `record` at this position.
+    pub(crate) fn errors(&self, record: &Record, hashes_per_rev: u64, schedule: &CycleSchedule) -> Vec<VerificationErrorKind> {
+        let (expected_rev, spent): (u64, Option<u64>) = self.position(record.rev_index);
+        let mut errors: Vec<VerificationErrorKind> = record.index_errors(expected_rev, schedule);
+        let remaining: u64 = hashes_per_rev.saturating_sub(spent.unwrap_or(0));
+        let num_hashes: u64 = record.hash_count(hashes_per_rev);
+
+        if num_hashes == 0 || num_hashes > remaining {
+            errors.push(VerificationErrorKind::HashCount { remaining, actual: num_hashes });
+        }
+        return errors;
+    }
+
+    // The rev the next entry belongs to, and the hashes already spent in it if known.
+    #[cfg(feature = "std")]
+    pub(crate) fn next_rev(&self) -> (u64, Option<u64>) {
+        return (self.next_rev, self.spent);
+    }
+
+    // The rev an entry at `rev_index` should have here, and the hashes already spent in it if known.
+    fn position(&self, rev_index: u64) -> (u64, Option<u64>) {
+        if self.spent.is_none() && rev_index == self.next_rev.saturating_add(1) {
+            return (rev_index, Some(0));
+        }
+        return (self.next_rev, self.spent);
+    }
+}
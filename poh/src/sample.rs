@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+
+use crate::{
+    record::Cursor,
+    types::{PoH, Record, SampleConfig, SampleReport, SampledVerifier},
+};
+
+use lib::{
+    hash::{ChainHasher, Hasher},
+    metronome::{ClockConfig, CycleSchedule},
+};
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+impl Default for SampleConfig {
+    fn default() -> Self {
+        return Self {
+            samples: 64,
+            seed: 0,
+            forged_fraction: 0.01,
+            verify_events: true,
+            clock: ClockConfig::default(),
+        };
+    }
+}
+
+impl PoH {
+    pub fn sample_records(records: &[Record], config: SampleConfig) -> SampledVerifier<'_> {
+        return Self::sample_records_with(Hasher::default(), records, config);
+    }
+}
+
+impl<H: ChainHasher> PoH<H> {
+    /// Checks indices on every record and re-hashes every event segment, then re-hashes `config.samples`
+    /// tick segments picked by an RNG seeded with `config.seed`.
+    pub fn sample_records_with(hasher: H, records: &[Record], config: SampleConfig) -> SampledVerifier<'_, H> {
+        let mut verifier: SampledVerifier<'_, H> = SampledVerifier::new(hasher, records, config);
+        verifier.sample(config.samples);
+        return verifier;
+    }
+}
+
+impl<'a, H: ChainHasher> SampledVerifier<'a, H> {
+    fn new(hasher: H, records: &'a [Record], config: SampleConfig) -> Self {
+        let segments: usize = records.len().saturating_sub(1);
+        let mut verifier: Self = Self {
+            hasher,
+            records,
+            config,
+            rng: ChaCha8Rng::seed_from_u64(config.seed),
+            ticks: 0,
+            pending: Vec::new(),
+            report: SampleReport {
+                valid: !records.is_empty(),
+                failed_segment: None,
+                segments,
+                verified_segments: 0,
+                confidence: 0.0,
+            },
+        };
+
+        let schedule: CycleSchedule = config.clock.schedule();
+        let mut cursor: Option<Cursor> = None;
+        for (index, record) in records.iter().enumerate() {
+            if cursor.is_some_and(|from| !from.errors(record, config.clock.hashes_per_rev, &schedule).is_empty()) {
+                verifier.fail(index.saturating_sub(1));
+                return verifier;
+            }
+            cursor = Some(Cursor::after(cursor.as_ref(), record, config.clock.hashes_per_rev));
+        }
+
+        for (segment, record) in records.iter().skip(1).enumerate() {
+            if config.verify_events && !record.events.is_empty() {
+                if !verifier.verify_segment(segment) {
+                    return verifier;
+                }
+            } else {
+                verifier.pending.push(segment);
+            }
+        }
+
+        verifier.ticks = verifier.pending.len();
+        verifier.update_confidence();
+        return verifier;
+    }
+
+    /// Re-hashes up to `count` more tick segments, stopping at the first failure.
+    pub fn sample(&mut self, count: usize) -> SampleReport {
+        for _ in 0..count {
+            if !self.report.valid || self.pending.is_empty() {
+                break;
+            }
+
+            let pick: usize = self.rng.random_range(0..self.pending.len());
+            let segment: usize = self.pending.swap_remove(pick);
+
+            if !self.verify_segment(segment) {
+                break;
+            }
+        }
+
+        self.update_confidence();
+        return self.report;
+    }
+
+    /// Keeps sampling `batch` segments at a time until `target_confidence` is reached, a segment fails
+    /// or every segment has been re-hashed.
+    pub fn sample_until(&mut self, target_confidence: f64, batch: usize) -> SampleReport {
+        while self.report.valid && self.report.confidence < target_confidence && !self.pending.is_empty() {
+            self.sample(batch.max(1));
+        }
+        return self.report;
+    }
+
+    pub fn report(&self) -> SampleReport {
+        return self.report;
+    }
+
+    fn verify_segment(&mut self, segment: usize) -> bool {
+        let prev: &Record = &self.records[segment];
+        let curr: &Record = &self.records[segment.saturating_add(1)];
+
+        let hashes_per_rev: u64 = self.config.clock.hashes_per_rev;
+        if !self
+            .hasher
+            .verify_hash_chain(&curr.mix_events(&self.hasher, &prev.hash), &curr.hash, curr.hash_count(hashes_per_rev), None)
+        {
+            self.fail(segment);
+            return false;
+        }
+
+        self.report.verified_segments = self.report.verified_segments.saturating_add(1);
+        return true;
+    }
+
+    fn fail(&mut self, segment: usize) {
+        self.report.valid = false;
+        self.report.failed_segment = Some(segment);
+    }
+
+    // Probability that the tick segments sampled so far, drawn without replacement, include at least
+    // one of `forged_fraction` (at least one) forged tick segments.
+    #[allow(clippy::arithmetic_side_effects)]
+    fn update_confidence(&mut self) {
+        // Fewer than two records leave no segment to check, so nothing backs the ledger.
+        if self.report.segments == 0 {
+            self.report.confidence = 0.0;
+            return;
+        }
+        if self.pending.is_empty() {
+            self.report.confidence = 1.0;
+            return;
+        }
+
+        let sampled: usize = self.ticks.saturating_sub(self.pending.len());
+        let forged_share: f64 = self.config.forged_fraction.clamp(0.0, 1.0) * self.ticks as f64;
+        let mut forged: usize = forged_share as usize;
+
+        if (forged as f64) < forged_share {
+            forged = forged.saturating_add(1);
+        }
+        forged = forged.max(1);
+
+        let mut missed: f64 = 1.0;
+        for i in 0..sampled {
+            let clean: usize = self.ticks.saturating_sub(forged).saturating_sub(i);
+            missed *= clean as f64 / self.ticks.saturating_sub(i) as f64;
+        }
+
+        self.report.confidence = 1.0 - missed;
+    }
+}
@@ -0,0 +1,158 @@
+use std::{
+    mem,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{Receiver, SendError, SyncSender, TrySendError, sync_channel},
+    },
+};
+
+use crate::types::{EventSender, PoH, PoHService, Record, ServiceConfig};
+
+use lib::{
+    clock::Clock,
+    hash::ChainHasher,
+    metronome::{DEFAULT_BATCH_SIZE, DEFAULT_CHANNEL_CAPACITY},
+};
+
+use anyhow::{Result, anyhow};
+use thread::native_runtime::types::{Config, JoinHandle, Native};
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        return Self {
+            name: "poh".to_string(),
+            thread: Config {
+                max_threads: 1,
+                ..Config::default()
+            },
+            channel_capacity: DEFAULT_CHANNEL_CAPACITY,
+            batch_size: DEFAULT_BATCH_SIZE,
+        };
+    }
+}
+
+impl PoHService {
+    /// Starts a generator from `seed` with the default hasher, profile and clock.
+    pub fn start(seed: &[u8], config: &ServiceConfig) -> Result<Self> {
+        return Self::spawn(PoH::new(seed), config);
+    }
+}
+
+impl<H, C> PoHService<H, C>
+where
+    H: ChainHasher + Send + 'static,
+    C: Clock + Send + 'static,
+{
+    /// Hands `poh` to a new generator thread, which continues its chain.
+    pub fn spawn(poh: PoH<H, C>, config: &ServiceConfig) -> Result<Self> {
+        let capacity: usize = config.channel_capacity.max(1);
+        let batch_size: usize = config.batch_size.max(1);
+        let (event_tx, event_rx) = sync_channel::<Vec<u8>>(capacity);
+        let (record_tx, record_rx) = sync_channel::<Vec<Record>>(capacity);
+        let shutdown: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+
+        let worker: Native = Native::new(config.name.clone(), config.thread.clone())?;
+        let stop: Arc<AtomicBool> = shutdown.clone();
+        let handle: JoinHandle<PoH<H, C>> = worker.spawn(move || generate(poh, event_rx, record_tx, stop, capacity, batch_size))?;
+
+        return Ok(Self {
+            events: EventSender { tx: event_tx },
+            records: record_rx,
+            shutdown,
+            handle: Some(handle),
+        });
+    }
+}
+
+impl<H, C> PoHService<H, C> {
+    pub fn sender(&self) -> EventSender {
+        return self.events.clone();
+    }
+
+    /// Record batches in chain order.
+    pub fn records(&self) -> &Receiver<Vec<Record>> {
+        return &self.records;
+    }
+
+    /// Stops the generator once the events already queued are recorded and its rev has ended.
+    /// Events still being submitted meanwhile may be dropped, and submitting fails once it has stopped.
+    ///
+    /// Returns the generator, to continue the chain from, and the records not received yet.
+    pub fn shutdown(mut self) -> Result<(PoH<H, C>, Vec<Record>)> {
+        let records: Vec<Record> = self.stop();
+        let handle: JoinHandle<PoH<H, C>> = self.handle.take().ok_or_else(|| anyhow!("PoH service already stopped."))?;
+        let name: String = handle.name().to_string();
+        let poh: PoH<H, C> = handle.join().map_err(|_| anyhow!("PoH thread '{}' panicked.", name))?;
+        return Ok((poh, records));
+    }
+
+    // Signals the generator and drains its output until it exits, so it never blocks on a full channel.
+    fn stop(&self) -> Vec<Record> {
+        self.shutdown.store(true, Ordering::Release);
+        return self.records.iter().flatten().collect();
+    }
+}
+
+impl<H, C> Drop for PoHService<H, C> {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.stop();
+            // Ignore any panic when dropping.
+            let _ = handle.join();
+        }
+    }
+}
+
+impl EventSender {
+    /// Queues an event, blocking while the queue is full. Fails once the service has stopped.
+    pub fn submit(&self, event: Vec<u8>) -> Result<(), SendError<Vec<u8>>> {
+        return self.tx.send(event);
+    }
+
+    /// Queues an event, handing it back if the queue is full or the service has stopped.
+    pub fn try_submit(&self, event: Vec<u8>) -> Result<(), TrySendError<Vec<u8>>> {
+        return self.tx.try_send(event);
+    }
+}
+
+// Generator loop: mixes queued events into one entry, then ends the rev with a tick.
+fn generate<H: ChainHasher, C: Clock>(
+    mut poh: PoH<H, C>,
+    events: Receiver<Vec<u8>>,
+    records: SyncSender<Vec<Record>>,
+    shutdown: Arc<AtomicBool>,
+    capacity: usize,
+    batch_size: usize,
+) -> PoH<H, C> {
+    let mut batch: Vec<Record> = Vec::with_capacity(batch_size);
+
+    loop {
+        let stopping: bool = shutdown.load(Ordering::Acquire);
+        let pending: Vec<Vec<u8>> = events.try_iter().take(capacity).collect();
+
+        if !pending.is_empty() {
+            let refs: Vec<&[u8]> = pending.iter().map(Vec::as_slice).collect();
+            batch.push(poh.insert_events(&refs));
+        }
+
+        // Stop after what was queued, at most a full channel, so producers that keep submitting cannot hold it open.
+        if stopping {
+            break;
+        }
+        batch.push(poh.next_rev());
+
+        if batch.len() >= batch_size && records.send(mem::take(&mut batch)).is_err() {
+            return poh;
+        }
+    }
+
+    // End on a rev boundary, so the chain can be anchored after the last record.
+    if poh.rev_hashes > 0 {
+        batch.push(poh.next_rev());
+    }
+    if !batch.is_empty() {
+        let _ = records.send(batch);
+    }
+    return poh;
+}
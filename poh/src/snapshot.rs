@@ -0,0 +1,114 @@
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::types::{Anchor, LedgerError, PoH, ResumeError, Snapshot, SnapshotError};
+
+#[cfg(feature = "std")]
+use lib::clock::unix_time_us;
+use lib::{
+    clock::Clock,
+    hash::Hasher,
+    metronome::{ClockConfigError, CycleSchedule},
+    profile::ProfileConfig,
+};
+
+#[cfg(feature = "std")]
+impl<C: Clock> PoH<Hasher, C> {
+    /// [`PoH::snapshot_at`] the current system time.
+    pub fn snapshot(&self) -> Snapshot {
+        return self.snapshot_at(unix_time_us());
+    }
+
+    /// [`PoH::restore_at`] the current system time.
+    pub fn restore(snapshot: &Snapshot, hasher: Hasher, clock: C) -> Result<Self, SnapshotError> {
+        return Self::restore_at(snapshot, hasher, clock, unix_time_us());
+    }
+}
+
+impl<C: Clock> PoH<Hasher, C> {
+    /// Captures the generator's position, with `unix_us` the wall-clock time matching the clock's `now_us()`.
+    pub fn snapshot_at(&self, unix_us: u64) -> Snapshot {
+        return Snapshot {
+            meta: self.ledger_meta(),
+            current_hash: self.current_hash,
+            rev_count: self.rev_count,
+            phase_count: self.phase_count,
+            cycle_count: self.cycle_count,
+            rev_hashes: self.rev_hashes,
+            next_rev_unix_us: shift(self.next_rev_target_us, self.clock.now_us(), unix_us),
+        };
+    }
+
+    /// Rebuilds a generator from `snapshot` on `hasher`, with `unix_us` the wall-clock time matching `clock.now_us()`.
+    ///
+    /// `hasher` must match the algorithm and mode the snapshot records.
+    ///
+    /// Revs stay due when they were before the snapshot was taken, so revs missed while stopped are produced
+    /// without waiting until the generator is back on schedule. A generator on UNIX time stays on it, from the same genesis.
+    pub fn restore_at(snapshot: &Snapshot, hasher: Hasher, clock: C, unix_us: u64) -> Result<Self, SnapshotError> {
+        let profile: ProfileConfig = snapshot.meta.profile;
+        profile.clock.validate()?;
+        snapshot.meta.check(&hasher, &profile)?;
+
+        let schedule: CycleSchedule = profile.clock.schedule();
+        let counters: (u64, u64) = schedule.cycle_of_phase(schedule.phase_of_rev(snapshot.rev_count));
+        if counters != (snapshot.cycle_count, snapshot.phase_count) || snapshot.rev_hashes >= profile.clock.hashes_per_rev {
+            return Err(SnapshotError::Counters);
+        }
+
+        let anchor: Anchor = Anchor {
+            hash: snapshot.current_hash,
+            next_rev: snapshot.rev_count,
+        };
+        let mut poh: Self = Self::resume(hasher, &profile, clock, &anchor, snapshot.rev_hashes)?;
+        if let Some(genesis_unix_us) = snapshot.meta.genesis_unix_us {
+            poh = poh.at_unix_time(unix_us);
+            poh.genesis_us = genesis_unix_us;
+        }
+        poh.next_rev_target_us = shift(snapshot.next_rev_unix_us, unix_us, poh.clock.now_us());
+        return Ok(poh);
+    }
+}
+
+// Moves `time` from a timeline where it is now `from` onto one where it is now `to`, clamped at the new origin.
+fn shift(time: u64, from: u64, to: u64) -> u64 {
+    return match time.checked_sub(from) {
+        Some(ahead) => to.saturating_add(ahead),
+        None => to.saturating_sub(from.saturating_sub(time)),
+    };
+}
+
+impl From<ClockConfigError> for SnapshotError {
+    fn from(error: ClockConfigError) -> Self {
+        return SnapshotError::Clock(error);
+    }
+}
+
+impl From<LedgerError> for SnapshotError {
+    fn from(error: LedgerError) -> Self {
+        return SnapshotError::Hasher(error);
+    }
+}
+
+impl From<ResumeError> for SnapshotError {
+    fn from(error: ResumeError) -> Self {
+        return match error {
+            ResumeError::Clock(error) => SnapshotError::Clock(error),
+            ResumeError::RevHashes { .. } => SnapshotError::Counters,
+        };
+    }
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        return match self {
+            SnapshotError::Clock(error) => write!(f, "Snapshot clock config is invalid: {}", error),
+            SnapshotError::Counters => write!(f, "Snapshot counters do not match its clock schedule."),
+            SnapshotError::Hasher(error) => write!(f, "Snapshot cannot be restored with this hasher: {}", error),
+        };
+    }
+}
+
+impl Error for SnapshotError {}
@@ -0,0 +1,360 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    record::Cursor,
+    types::{Anchor, FsyncPolicy, LedgerHeader, LedgerMeta, LedgerStore, PoH, Record, StoreConfig, VerificationError, VerificationReport, VerifyMode},
+};
+
+use lib::{
+    clock::{Clock, unix_time_us},
+    hash::{Hash, Hasher},
+    metronome::{ClockConfig, DEFAULT_REVS_PER_PHASE},
+};
+
+use anyhow::{Context, Result, bail};
+
+const HEADER_FILE: &str = "ledger.json";
+const LOG_EXTENSION: &str = "log";
+const INDEX_EXTENSION: &str = "idx";
+// A little-endian rev, then the byte offset of its first entry in the segment log.
+const INDEX_ENTRY_BYTES: usize = 16;
+
+impl Default for StoreConfig {
+    fn default() -> Self {
+        return Self {
+            // 1024 phases of the default schedule.
+            segment_revs: DEFAULT_REVS_PER_PHASE * 1_024,
+            fsync: FsyncPolicy::default(),
+        };
+    }
+}
+
+impl LedgerStore {
+    /// Starts an empty ledger in `dir`, failing if it already holds one.
+    pub fn create(dir: impl AsRef<Path>, meta: LedgerMeta, seed: &[u8], config: StoreConfig) -> Result<Self> {
+        let dir: PathBuf = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).with_context(|| format!("Failed to create ledger directory '{}'.", dir.display()))?;
+
+        let header_path: PathBuf = dir.join(HEADER_FILE);
+        if header_path.exists() {
+            bail!("A ledger already exists in '{}'.", dir.display());
+        }
+
+        let header: LedgerHeader = LedgerHeader {
+            meta,
+            genesis: Anchor::genesis(&meta.hasher(), seed),
+        };
+        let mut file: File = File::create(&header_path)?;
+        file.write_all(&serde_json::to_vec_pretty(&header)?)?;
+        file.sync_all()?;
+        sync_dir(&dir)?;
+        if let Some(parent) = dir.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            sync_dir(parent)?;
+        }
+
+        return Ok(Self::empty(dir, header, config));
+    }
+
+    /// Opens the ledger in `dir`, truncating a torn tail write so the store ends on its last durable record.
+    pub fn open(dir: impl AsRef<Path>, config: StoreConfig) -> Result<Self> {
+        let dir: PathBuf = dir.as_ref().to_path_buf();
+        let header_path: PathBuf = dir.join(HEADER_FILE);
+        let header: LedgerHeader = serde_json::from_slice(&fs::read(&header_path).with_context(|| format!("No ledger in '{}'.", dir.display()))?)
+            .with_context(|| format!("Invalid ledger header '{}'.", header_path.display()))?;
+
+        let mut store: Self = Self::empty(dir, header, config);
+        store.segments = store.list_segments()?;
+
+        // A tail segment left without durable records is dropped, and the one before it becomes the tail.
+        while let Some(first_rev) = store.segments.last().copied() {
+            if store.recover_tail(first_rev)? {
+                break;
+            }
+            fs::remove_file(store.segment_path(first_rev, LOG_EXTENSION))?;
+            let _ = fs::remove_file(store.segment_path(first_rev, INDEX_EXTENSION));
+            store.segments.pop();
+            sync_dir(&store.dir)?;
+        }
+        return Ok(store);
+    }
+
+    pub fn header(&self) -> &LedgerHeader {
+        return &self.header;
+    }
+
+    /// The rev the next appended record belongs to.
+    pub fn next_rev(&self) -> u64 {
+        return self.cursor.next_rev().0;
+    }
+
+    /// Appends records that continue the ledger, syncing them as the fsync policy asks.
+    ///
+    /// Indices, hash counts and the hash chain from the last stored record are all checked, so every record is re-hashed.
+    /// The whole batch is checked before any of it is written, so a rejected batch leaves the ledger as it was.
+    pub fn append(&mut self, records: &[Record]) -> Result<()> {
+        let hasher: Hasher = self.header.meta.hasher();
+        let config: ClockConfig = self.header.meta.profile.clock;
+        let hashes_per_rev: u64 = config.hashes_per_rev;
+
+        let mut cursor: Cursor = self.cursor;
+        for (index, record) in records.iter().enumerate() {
+            if let Some(error) = chain_error(&hasher, &config, &cursor, record) {
+                bail!(
+                    "Record {} of the batch does not continue the ledger. {}",
+                    index,
+                    VerificationError { index: None, ..error }
+                );
+            }
+            cursor = Cursor::after(Some(&cursor), record, hashes_per_rev);
+        }
+
+        for record in records {
+            let starts_rev: bool = self.cursor.next_rev() == (record.rev_index, Some(0));
+            let segment_end: u64 = self.segments.last().map_or(0, |first_rev| first_rev.saturating_add(self.config.segment_revs));
+            if starts_rev && (self.log.is_none() || record.rev_index >= segment_end) {
+                self.rotate(record.rev_index)?;
+            }
+
+            let mut line: Vec<u8> = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            if starts_rev {
+                let entry: [u8; INDEX_ENTRY_BYTES] = index_entry(record.rev_index, self.log_len);
+                self.index_file()?.write_all(&entry)?;
+            }
+            self.log_file()?.write_all(&line)?;
+
+            self.log_len = self.log_len.saturating_add(line.len() as u64);
+            self.cursor = Cursor::after(Some(&self.cursor), record, hashes_per_rev);
+            self.unsynced = self.unsynced.saturating_add(1);
+        }
+
+        return match self.config.fsync {
+            FsyncPolicy::Always => self.sync(),
+            FsyncPolicy::EveryRecords(count) if self.unsynced >= count => self.sync(),
+            _ => Ok(()),
+        };
+    }
+
+    /// Forces appended records to disk.
+    pub fn sync(&mut self) -> Result<()> {
+        if let Some(index) = &self.index {
+            index.sync_data()?;
+        }
+        if let Some(log) = &self.log {
+            log.sync_data()?;
+        }
+        self.unsynced = 0;
+        return Ok(());
+    }
+
+    /// Every record, in chain order.
+    pub fn records(&self) -> Result<Vec<Record>> {
+        let mut records: Vec<Record> = Vec::new();
+        for first_rev in &self.segments {
+            records.extend(parse_lines(&fs::read(self.segment_path(*first_rev, LOG_EXTENSION))?)?);
+        }
+        return Ok(records);
+    }
+
+    /// Records from the first entry of `rev` on, located through the rev index. Empty past the end of the ledger.
+    pub fn read_from(&self, rev: u64) -> Result<Vec<Record>> {
+        let position: usize = self.segments.partition_point(|first_rev| *first_rev <= rev);
+        let Some(segment) = position.checked_sub(1) else {
+            return self.records();
+        };
+
+        let first_rev: u64 = self.segments[segment];
+        let index: Vec<u8> = fs::read(self.segment_path(first_rev, INDEX_EXTENSION))?;
+        let entries: Vec<(u64, u64)> = index.chunks_exact(INDEX_ENTRY_BYTES).map(parse_index_entry).collect();
+        let Ok(entry) = entries.binary_search_by_key(&rev, |(entry_rev, _)| *entry_rev) else {
+            return Ok(Vec::new());
+        };
+
+        let log: Vec<u8> = fs::read(self.segment_path(first_rev, LOG_EXTENSION))?;
+        let offset: usize = usize::try_from(entries[entry].1).unwrap_or(usize::MAX).min(log.len());
+        let mut records: Vec<Record> = parse_lines(&log[offset..])?;
+        for first_rev in &self.segments[position..] {
+            records.extend(parse_lines(&fs::read(self.segment_path(*first_rev, LOG_EXTENSION))?)?);
+        }
+        return Ok(records);
+    }
+
+    /// A generator positioned right after the last durable record, continuing the same chain.
+    pub fn resume<C: Clock>(&self, clock: C) -> Result<PoH<Hasher, C>> {
+        let (next_rev, spent): (u64, Option<u64>) = self.cursor.next_rev();
+        let anchor: Anchor = Anchor {
+            hash: self.cursor.hash,
+            next_rev,
+        };
+        let meta: LedgerMeta = self.header.meta;
+        let mut poh: PoH<Hasher, C> = PoH::resume(meta.hasher(), &meta.profile, clock, &anchor, spent.unwrap_or(0))?;
+        // Keep stamping UNIX time when the ledger was.
+        if let Some(genesis_unix_us) = meta.genesis_unix_us {
+            poh = poh.at_unix_time(unix_time_us());
+            poh.genesis_us = genesis_unix_us;
+        }
+        return Ok(poh);
+    }
+
+    fn empty(dir: PathBuf, header: LedgerHeader, config: StoreConfig) -> Self {
+        return Self {
+            dir,
+            header,
+            config,
+            segments: Vec::new(),
+            log: None,
+            index: None,
+            log_len: 0,
+            cursor: Cursor::at(&header.genesis),
+            unsynced: 0,
+        };
+    }
+
+    fn segment_path(&self, first_rev: u64, extension: &str) -> PathBuf {
+        return self.dir.join(format!("{:020}.{}", first_rev, extension));
+    }
+
+    fn list_segments(&self) -> Result<Vec<u64>> {
+        let mut segments: Vec<u64> = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path: PathBuf = entry?.path();
+            let first_rev: Option<u64> = path
+                .extension()
+                .filter(|extension| *extension == LOG_EXTENSION)
+                .and_then(|_| path.file_stem()?.to_str()?.parse::<u64>().ok());
+            segments.extend(first_rev);
+        }
+        segments.sort_unstable();
+        return Ok(segments);
+    }
+
+    // Scans the tail segment, cuts it before its first record that does not continue the chain and rebuilds
+    // its index. Returns whether any record survived.
+    fn recover_tail(&mut self, first_rev: u64) -> Result<bool> {
+        let hasher: Hasher = self.header.meta.hasher();
+        let config: ClockConfig = self.header.meta.profile.clock;
+        let log_path: PathBuf = self.segment_path(first_rev, LOG_EXTENSION);
+        let bytes: Vec<u8> = fs::read(&log_path)?;
+
+        // Segments start on a rev boundary, right after the last record of the one before, which was synced
+        // before this one was created, or at genesis.
+        let hash: Hash = match self.segments.iter().rev().nth(1) {
+            Some(prev_rev) => match parse_lines(&fs::read(self.segment_path(*prev_rev, LOG_EXTENSION))?)?.last() {
+                Some(last) => last.hash,
+                None => bail!("Ledger segment {} is empty.", prev_rev),
+            },
+            None => self.header.genesis.hash,
+        };
+        let mut cursor: Cursor = Cursor::at(&Anchor { hash, next_rev: first_rev });
+        let mut index: Vec<u8> = Vec::new();
+        let mut durable: usize = 0;
+        let mut found: bool = false;
+
+        for line in bytes.split_inclusive(|byte| *byte == b'\n') {
+            if line.last() != Some(&b'\n') {
+                break;
+            }
+            let Ok(record) = serde_json::from_slice::<Record>(line) else {
+                break;
+            };
+            if chain_error(&hasher, &config, &cursor, &record).is_some() {
+                break;
+            }
+
+            if cursor.next_rev() == (record.rev_index, Some(0)) {
+                index.extend_from_slice(&index_entry(record.rev_index, durable as u64));
+            }
+            cursor = Cursor::after(Some(&cursor), &record, config.hashes_per_rev);
+            durable = durable.saturating_add(line.len());
+            found = true;
+        }
+
+        if !found {
+            return Ok(false);
+        }
+
+        let log: File = OpenOptions::new().append(true).open(&log_path)?;
+        if durable < bytes.len() {
+            log.set_len(durable as u64)?;
+            log.sync_all()?;
+        }
+        let mut index_file: File = File::create(self.segment_path(first_rev, INDEX_EXTENSION))?;
+        index_file.write_all(&index)?;
+        index_file.sync_all()?;
+
+        self.log = Some(log);
+        self.index = Some(index_file);
+        self.log_len = durable as u64;
+        self.cursor = cursor;
+        return Ok(true);
+    }
+
+    // Starts a new segment at `first_rev`, after syncing the current one. The directory is synced too, so the
+    // new files outlive a crash along with the records later synced into them.
+    fn rotate(&mut self, first_rev: u64) -> Result<()> {
+        self.sync()?;
+        self.log = Some(File::create(self.segment_path(first_rev, LOG_EXTENSION))?);
+        self.index = Some(File::create(self.segment_path(first_rev, INDEX_EXTENSION))?);
+        sync_dir(&self.dir)?;
+        self.segments.push(first_rev);
+        self.log_len = 0;
+        return Ok(());
+    }
+
+    fn log_file(&mut self) -> Result<&mut File> {
+        return self.log.as_mut().context("No open ledger segment.");
+    }
+
+    fn index_file(&mut self) -> Result<&mut File> {
+        return self.index.as_mut().context("No open ledger segment.");
+    }
+}
+
+impl Drop for LedgerStore {
+    fn drop(&mut self) {
+        // Ignore any error when dropping.
+        let _ = self.sync();
+    }
+}
+
+// Makes entries created in or removed from `dir` durable.
+fn sync_dir(dir: &Path) -> Result<()> {
+    File::open(dir)?
+        .sync_all()
+        .with_context(|| format!("Failed to sync directory '{}'.", dir.display()))?;
+    return Ok(());
+}
+
+// The first reason `record` does not continue the chain at `cursor`, re-hashing it.
+fn chain_error(hasher: &Hasher, config: &ClockConfig, cursor: &Cursor, record: &Record) -> Option<VerificationError> {
+    let mut report: VerificationReport = VerificationReport::new(1);
+    report.check_record(hasher, config, cursor, 0, record, VerifyMode::StopAtFirst);
+    return report.into_result().err();
+}
+
+fn index_entry(rev: u64, offset: u64) -> [u8; INDEX_ENTRY_BYTES] {
+    let mut entry: [u8; INDEX_ENTRY_BYTES] = [0u8; INDEX_ENTRY_BYTES];
+    entry[..8].copy_from_slice(&rev.to_le_bytes());
+    entry[8..].copy_from_slice(&offset.to_le_bytes());
+    return entry;
+}
+
+fn parse_index_entry(entry: &[u8]) -> (u64, u64) {
+    let mut rev: [u8; 8] = [0u8; 8];
+    let mut offset: [u8; 8] = [0u8; 8];
+    rev.copy_from_slice(&entry[..8]);
+    offset.copy_from_slice(&entry[8..]);
+    return (u64::from_le_bytes(rev), u64::from_le_bytes(offset));
+}
+
+fn parse_lines(bytes: &[u8]) -> Result<Vec<Record>> {
+    return bytes
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice::<Record>(line).context("Corrupt ledger record."))
+        .collect();
+}
@@ -1,27 +1,369 @@
-use std::time::Instant;
+#[cfg(feature = "std")]
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::{
+    fs::File,
+    path::PathBuf,
+    sync::{
+        Arc,
+        atomic::AtomicBool,
+        mpsc::{Receiver, SyncSender},
+    },
+};
 
-use crate::serializer;
+#[cfg(not(feature = "std"))]
+use lib::clock::FastForwardClock;
+#[cfg(feature = "std")]
+use lib::clock::MonotonicClock;
+use lib::{
+    hash::{Algorithm, Hash, HashMode, Hasher},
+    metronome::{ClockConfig, ClockConfigError},
+    profile::{Profile, ProfileConfig},
+};
 
+#[cfg(feature = "std")]
+use crate::record::Cursor;
+
+use rand_chacha::ChaCha8Rng;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use thread::native_runtime::types::{Config, JoinHandle};
+
+// Real time with std; without it there is no time source, so revs are fast-forwarded.
+#[cfg(feature = "std")]
+pub type DefaultClock = MonotonicClock;
+#[cfg(not(feature = "std"))]
+pub type DefaultClock = FastForwardClock;
+
+/// Current binary record layout, all integers little-endian:
+///
+/// | Offset | Size | Field                                          |
+/// |--------|------|------------------------------------------------|
+/// | 0      | 1    | version                                        |
+/// | 1      | 1    | flags, bit 0 set when `num_hashes` is present  |
+/// | 2      | 32   | hash                                           |
+/// | 34     | 8    | rev index                                      |
+/// | 42     | 8    | phase index                                    |
+/// | 50     | 8    | cycle index                                    |
+/// | 58     | 8    | timestamp in milliseconds                      |
+/// | 66     | 8    | num hashes, 0 when absent                      |
+/// | 74     | 4    | event count                                    |
+/// | 78     |      | per event, a 4 byte length then its payload    |
+pub const RECORD_VERSION: u8 = 1;
+
+/// Size of an encoded record without events.
+pub const RECORD_HEADER_BYTES: usize = 78;
 
 #[derive(Clone)]
-pub struct PoH {
-    pub current_hash: [u8; 32],
+pub struct PoH<H = Hasher, C = DefaultClock> {
+    pub hasher: H,
+    pub profile: Profile,
+    pub config: ClockConfig,
+    pub current_hash: Hash,
     pub rev_count: u64,
     pub phase_count: u64,
     pub cycle_count: u64,
-    pub start_time: Instant,
+    // Hashes already spent in the current rev by entries recorded mid-rev.
+    pub rev_hashes: u64,
+    pub clock: C,
     pub next_rev_target_us: u64,
+    // UNIX time in microseconds at the clock's zero, once anchored to wall time with `at_unix_time`.
+    pub epoch_unix_us: Option<u64>,
+    // When rev 0 started, in microseconds on the timeline of record timestamps.
+    pub genesis_us: u64,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+/// A ledger entry: `num_hashes` hashes since the previous entry, with `events` mixed in first.
+///
+/// Entries of one rev share its `rev_index`, and the rev ends with the entry that spends its last hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "crate::record::RecordFields")]
 pub struct Record {
-    #[serde(with = "serializer")]
-    pub hash: [u8; 32],
+    pub hash: Hash,
     pub rev_index: u64,
     pub phase_index: u64,
     pub cycle_index: u64,
+    // UNIX time in milliseconds, or time since the clock started for generators not anchored to wall time.
     pub timestamp_ms: u64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub event: Option<Vec<u8>>,
+    // `None` for an entry spanning its whole rev.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub num_hashes: Option<u64>,
+    // Also read from the single `event` of records written before entries carried several.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<Vec<u8>>,
+}
+
+/// Consecutive whole-rev ticks collapsed into their first rev and last hash, see [`PoH::compact_records`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickRun {
+    pub start_rev: u64,
+    pub count: u64,
+    // Hash of the last tick, the ones before it are re-computed on expansion.
+    pub hash: Hash,
+    // Time of the first tick in microseconds, each later tick stamped one `us_per_rev` after the one before.
+    pub start_us: u64,
+}
+
+/// An entry of a compacted ledger: a record kept as is, or a run of plain ticks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompactEntry {
+    Record(Record),
+    Ticks(TickRun),
+}
+
+/// A binary-encoded [`Record`] decoded in place, its events borrowing from the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordRef<'a> {
+    pub hash: Hash,
+    pub rev_index: u64,
+    pub phase_index: u64,
+    pub cycle_index: u64,
+    pub timestamp_ms: u64,
+    pub num_hashes: Option<u64>,
+    pub events: Events<'a>,
+}
+
+/// Length-prefixed event payloads of a [`RecordRef`], checked when it was decoded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Events<'a> {
+    pub(crate) bytes: &'a [u8],
+    pub(crate) remaining: u32,
+}
+
+/// Why a record does not fit the binary layout, whose counts and lengths are 32-bit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodeError {
+    TooManyEvents(usize),
+    EventTooLarge { index: usize, len: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    Truncated { needed: usize, available: usize },
+    UnknownVersion(u8),
+    UnknownFlags(u8),
+    // A hash count in the bytes while the flag says there is none, which no encoder writes.
+    UnflaggedHashCount(u64),
+}
+
+/// Stored alongside a ledger's records, so they are verified under the parameters they were produced with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerMeta {
+    pub profile: ProfileConfig,
+    pub algorithm: Algorithm,
+    #[serde(default)]
+    pub mode: HashMode,
+    // When rev 0 started in UNIX microseconds, `None` when timestamps count from the generator's start instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_unix_us: Option<u64>,
+}
+
+/// Why a ledger's metadata rules out verifying it with the given parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerError {
+    ProfileMismatch { expected: Profile, actual: Profile },
+    // Same profile name, but a different custom clock.
+    ClockMismatch,
+    AlgorithmMismatch { expected: Algorithm, actual: Algorithm },
+    ModeMismatch { expected: HashMode, actual: HashMode },
+}
+
+/// Trusted chain position to verify from: `hash` is what the record at `next_rev` extends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Anchor {
+    pub hash: Hash,
+    pub next_rev: u64,
+}
+
+/// Checkpoint of a running generator, restored by [`PoH::restore_at`] on the schedule it was taken from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub meta: LedgerMeta,
+    pub current_hash: Hash,
+    pub rev_count: u64,
+    pub phase_count: u64,
+    pub cycle_count: u64,
+    #[serde(default)]
+    pub rev_hashes: u64,
+    // UNIX time in microseconds at which the rev at `rev_count` is due to end.
+    pub next_rev_unix_us: u64,
+}
+
+/// Why a generator cannot continue a chain from the given position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResumeError {
+    Clock(ClockConfigError),
+    // The hashes already spent leave none for the rev, which would have ended already.
+    RevHashes { rev_hashes: u64, hashes_per_rev: u64 },
+}
+
+/// Why a snapshot cannot be restored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    Clock(ClockConfigError),
+    // Counters the schedule does not derive from `rev_count`, or a rev with no hashes left.
+    Counters,
+    // The hasher given to restore with is not the one the chain was generated with.
+    Hasher(LedgerError),
+}
+
+/// A record that failed verification, and why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerificationError {
+    // Index of the failing record, `None` when the ledger as a whole is rejected.
+    pub index: Option<usize>,
+    pub kind: VerificationErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerificationErrorKind {
+    EmptyInput,
+    HashMismatch { expected: Hash, actual: Hash },
+    RevGap { expected: u64, actual: u64 },
+    PhaseIndex { expected: u64, actual: u64 },
+    CycleIndex { expected: u64, actual: u64 },
+    // An entry must spend at least one hash, and no more than its rev has left.
+    HashCount { remaining: u64, actual: u64 },
+    TimestampDrift { expected_ms: u64, actual_ms: u64, allowed_ms: u64 },
+    TimestampBackwards { previous_ms: u64, actual_ms: u64 },
+    // A tick run with neither an entry nor an anchor before it to hash from.
+    UnanchoredRun,
+    // The metadata rules out verification, e.g. an algorithm mismatch.
+    Ledger(LedgerError),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyMode {
+    #[default]
+    StopAtFirst,
+    CollectAll,
+}
+
+/// How far record timestamps may stray from one rev duration per rev, see [`PoH::drift_report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DriftPolicy {
+    // Every timestamp within this many milliseconds of its scheduled time.
+    Absolute(u64),
+    // Deviations between consecutive records, added up, within this many milliseconds.
+    Cumulative(u64),
+    // Only that timestamps never go backwards.
+    Monotonic,
+}
+
+/// Failures found by a verification pass, in record order.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct VerificationReport {
+    pub records: usize,
+    pub errors: Vec<VerificationError>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleConfig {
+    // Tick segments re-hashed when the verifier is created.
+    pub samples: usize,
+    pub seed: u64,
+    // Smallest share of forged segments the reported confidence is computed against.
+    pub forged_fraction: f64,
+    // Always re-hash segments that mix in an event.
+    pub verify_events: bool,
+    pub clock: ClockConfig,
+}
+
+/// Outcome of sampled verification so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleReport {
+    pub valid: bool,
+    // Index `i` of the first failing segment `records[i] -> records[i + 1]`, if any.
+    pub failed_segment: Option<usize>,
+    pub segments: usize,
+    pub verified_segments: usize,
+    // Probability that `forged_fraction` of forged segments would have been caught.
+    pub confidence: f64,
+}
+
+/// Re-hashes a reproducible random subset of a ledger's segments, see [`PoH::sample_records`].
+pub struct SampledVerifier<'a, H = Hasher> {
+    pub(crate) hasher: H,
+    pub(crate) records: &'a [Record],
+    pub(crate) config: SampleConfig,
+    pub(crate) rng: ChaCha8Rng,
+    // Tick segments in the ledger, and those not sampled yet.
+    pub(crate) ticks: usize,
+    pub(crate) pending: Vec<usize>,
+    pub(crate) report: SampleReport,
+}
+
+/// Settings for [`PoHService`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct ServiceConfig {
+    pub name: String,
+    // Core pinning and priority of the generator thread.
+    pub thread: Config,
+    // Events queued before submitters block, also the most mixed into one entry.
+    pub channel_capacity: usize,
+    // Records sent per batch, the last batch may be shorter.
+    pub batch_size: usize,
+}
+
+/// Runs a PoH generator on a dedicated thread, recording submitted events and sending records in batches.
+#[cfg(feature = "std")]
+pub struct PoHService<H = Hasher, C = DefaultClock> {
+    pub(crate) events: EventSender,
+    pub(crate) records: Receiver<Vec<Record>>,
+    pub(crate) shutdown: Arc<AtomicBool>,
+    pub(crate) handle: Option<JoinHandle<PoH<H, C>>>,
+}
+
+/// Bounded submission handle for a [`PoHService`], cloned for each producer.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    pub(crate) tx: SyncSender<Vec<u8>>,
+}
+
+/// When appended records are forced to disk, records are always flushed to the OS.
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsyncPolicy {
+    // After every append call.
+    #[default]
+    Always,
+    // Once at least this many records were appended since the last sync.
+    EveryRecords(u64),
+    // Only on `sync`, segment rotation and drop.
+    Manual,
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoreConfig {
+    // Revs per segment file before a new one is started, at a rev boundary.
+    pub segment_revs: u64,
+    pub fsync: FsyncPolicy,
+}
+
+/// The `ledger.json` header of a ledger directory.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerHeader {
+    pub meta: LedgerMeta,
+    pub genesis: Anchor,
+}
+
+/// Append-only ledger directory: a header, then segment logs of JSON lines, each with a rev-index sidecar
+/// of little-endian `(rev, offset)` pairs pointing at the first entry of every rev.
+#[cfg(feature = "std")]
+pub struct LedgerStore {
+    pub(crate) dir: PathBuf,
+    pub(crate) header: LedgerHeader,
+    pub(crate) config: StoreConfig,
+    // First rev of every segment, in order, the last one open for appending.
+    pub(crate) segments: Vec<u64>,
+    pub(crate) log: Option<File>,
+    pub(crate) index: Option<File>,
+    pub(crate) log_len: u64,
+    pub(crate) cursor: Cursor,
+    pub(crate) unsynced: u64,
 }
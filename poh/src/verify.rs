@@ -0,0 +1,286 @@
+use alloc::vec::Vec;
+use core::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{
+    record::Cursor,
+    types::{Anchor, DriftPolicy, LedgerMeta, PoH, Record, VerificationError, VerificationErrorKind, VerificationReport, VerifyMode},
+};
+
+use lib::{
+    hash::{ChainHasher, Hash, Hasher},
+    metronome::ClockConfig,
+    profile::ProfileConfig,
+};
+
+// Timestamp tolerance per record under the default policy, relaxed for scheduling jitter.
+const ALLOWED_DRIFT_MS: u64 = 8;
+
+impl Default for DriftPolicy {
+    fn default() -> Self {
+        return DriftPolicy::Absolute(ALLOWED_DRIFT_MS);
+    }
+}
+
+impl PoH {
+    pub fn verify_records(records: &[Record]) -> Result<(), VerificationError> {
+        return Self::verify_records_with(&Hasher::default(), records);
+    }
+
+    /// Verifies a ledger with the default hasher and clock from the seed it was started with, including its first record.
+    pub fn verify_from_seed(seed: &[u8], records: &[Record]) -> Result<(), VerificationError> {
+        let hasher: Hasher = Hasher::default();
+        return Self::verify_anchored(&hasher, &ClockConfig::default(), &Anchor::genesis(&hasher, seed), records);
+    }
+
+    /// Verifies records with the hasher and clock recorded in `meta`, which must be under the `expected` profile.
+    pub fn verify_ledger(meta: &LedgerMeta, expected: &ProfileConfig, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::verify_ledger_with(&meta.hasher(), meta, expected, records);
+    }
+
+    /// Like [`PoH::verify_ledger`], failing if `hasher` is not the algorithm and mode recorded in `meta`.
+    pub fn verify_ledger_with(hasher: &Hasher, meta: &LedgerMeta, expected: &ProfileConfig, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::ledger_report(hasher, meta, expected, records, VerifyMode::StopAtFirst).into_result();
+    }
+
+    pub fn ledger_report(hasher: &Hasher, meta: &LedgerMeta, expected: &ProfileConfig, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        if let Err(error) = meta.check(hasher, expected) {
+            return VerificationReport {
+                records: records.len(),
+                errors: Vec::from([VerificationError {
+                    index: None,
+                    kind: VerificationErrorKind::Ledger(error),
+                }]),
+            };
+        }
+        return Self::records_report(hasher, &meta.profile.clock, records, mode);
+    }
+
+    pub fn verify_timestamps(records: &[Record]) -> Result<(), VerificationError> {
+        return Self::timestamps_report(&ClockConfig::default(), records, VerifyMode::StopAtFirst).into_result();
+    }
+
+    /// Checks each timestamp against the first one plus a rev duration per rev since, under the default [`DriftPolicy`].
+    pub fn timestamps_report(config: &ClockConfig, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        return Self::drift_report(config, None, DriftPolicy::default(), records, mode);
+    }
+
+    /// Checks timestamps under `policy`, against the ledger's UNIX genesis when it has one.
+    pub fn verify_ledger_timestamps(meta: &LedgerMeta, policy: DriftPolicy, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::drift_report(&meta.profile.clock, meta.genesis_unix_us, policy, records, VerifyMode::StopAtFirst).into_result();
+    }
+
+    /// Checks timestamps under `policy`. The schedule starts at `genesis_us`, when rev 0 started on the timeline
+    /// of the timestamps, or at the first record without one, and a rev's entries are due when the rev ends.
+    pub fn drift_report(config: &ClockConfig, genesis_us: Option<u64>, policy: DriftPolicy, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        let mut report: VerificationReport = VerificationReport::new(records.len());
+        let first: &Record = match records.first() {
+            Some(first) => first,
+            None => return report,
+        };
+        let origin: (u64, u64) = match genesis_us {
+            Some(genesis_us) => (genesis_us.saturating_add(config.us_per_rev), 0),
+            None => (first.timestamp_ms.saturating_mul(1_000), first.rev_index),
+        };
+        let mut spent_ms: u64 = 0;
+        let mut prev: Option<&Record> = None;
+
+        for (i, record) in records.iter().enumerate() {
+            let kind: Option<VerificationErrorKind> = match policy {
+                DriftPolicy::Absolute(allowed_ms) => {
+                    let expected_ms: u64 = scheduled_ms(config, origin, record.rev_index);
+                    let drift_ms: u64 = record.timestamp_ms.abs_diff(expected_ms);
+                    (drift_ms > allowed_ms).then_some(VerificationErrorKind::TimestampDrift {
+                        expected_ms,
+                        actual_ms: record.timestamp_ms,
+                        allowed_ms,
+                    })
+                }
+                DriftPolicy::Cumulative(budget_ms) => {
+                    // Held against the previous record's offset from the schedule, so a lasting offset is only charged once.
+                    let scheduled: u64 = scheduled_ms(config, origin, record.rev_index);
+                    let expected_ms: u64 = prev.map_or(scheduled, |prev| {
+                        scheduled
+                            .saturating_add(prev.timestamp_ms)
+                            .saturating_sub(scheduled_ms(config, origin, prev.rev_index))
+                    });
+                    let drift_ms: u64 = record.timestamp_ms.abs_diff(expected_ms);
+                    let allowed_ms: u64 = budget_ms.saturating_sub(spent_ms);
+                    spent_ms = spent_ms.saturating_add(drift_ms);
+                    (drift_ms > allowed_ms).then_some(VerificationErrorKind::TimestampDrift {
+                        expected_ms,
+                        actual_ms: record.timestamp_ms,
+                        allowed_ms,
+                    })
+                }
+                DriftPolicy::Monotonic => prev
+                    .filter(|prev| record.timestamp_ms < prev.timestamp_ms)
+                    .map(|prev| VerificationErrorKind::TimestampBackwards {
+                        previous_ms: prev.timestamp_ms,
+                        actual_ms: record.timestamp_ms,
+                    }),
+            };
+
+            if kind.is_some_and(|kind| !report.push(i, kind, mode)) {
+                break;
+            }
+            prev = Some(record);
+        }
+        return report;
+    }
+}
+
+// When the rev at `rev` ends, in milliseconds, given that the rev at `origin.1` ends at `origin.0` microseconds.
+fn scheduled_ms(config: &ClockConfig, origin: (u64, u64), rev: u64) -> u64 {
+    let revs: u64 = rev.saturating_sub(origin.1);
+    return origin.0.saturating_add(revs.saturating_mul(config.us_per_rev)) / 1_000;
+}
+
+impl<H: ChainHasher> PoH<H> {
+    pub fn verify_records_with(hasher: &H, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::verify_records_with_config(hasher, &ClockConfig::default(), records);
+    }
+
+    pub fn verify_records_with_config(hasher: &H, config: &ClockConfig, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::records_report(hasher, config, records, VerifyMode::StopAtFirst).into_result();
+    }
+
+    /// Checks every record against its predecessor: indices and hash counts first, then the hash chain. The first record is taken as given.
+    pub fn records_report(hasher: &H, config: &ClockConfig, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        return Self::chain_report(hasher, config, None, records, mode);
+    }
+
+    /// Verifies records starting from a trusted genesis or checkpoint, including the first record.
+    pub fn verify_anchored(hasher: &H, config: &ClockConfig, anchor: &Anchor, records: &[Record]) -> Result<(), VerificationError> {
+        return Self::anchored_report(hasher, config, anchor, records, VerifyMode::StopAtFirst).into_result();
+    }
+
+    pub fn anchored_report(hasher: &H, config: &ClockConfig, anchor: &Anchor, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        return Self::chain_report(hasher, config, Some(anchor), records, mode);
+    }
+
+    fn chain_report(hasher: &H, config: &ClockConfig, anchor: Option<&Anchor>, records: &[Record], mode: VerifyMode) -> VerificationReport {
+        let mut report: VerificationReport = VerificationReport::new(records.len());
+        let mut cursor: Option<Cursor> = anchor.map(Cursor::at);
+
+        for (index, record) in records.iter().enumerate() {
+            if cursor.is_some_and(|from| !report.check_record(hasher, config, &from, index, record, mode)) {
+                return report;
+            }
+            cursor = Some(Cursor::after(cursor.as_ref(), record, config.hashes_per_rev));
+        }
+        return report;
+    }
+}
+
+impl Anchor {
+    /// The start of a chain, before rev 0.
+    pub fn genesis<H: ChainHasher>(hasher: &H, seed: &[u8]) -> Self {
+        return Self {
+            hash: hasher.genesis_hash(seed),
+            next_rev: 0,
+        };
+    }
+
+    /// A trusted hash of the entry ending rev `rev_index`, so verification can start with the entry after it.
+    pub fn checkpoint(hash: Hash, rev_index: u64) -> Self {
+        return Self {
+            hash,
+            next_rev: rev_index.saturating_add(1),
+        };
+    }
+
+    /// Anchors after `record`, which must be the entry ending its rev.
+    pub fn after(record: &Record) -> Self {
+        return Self::checkpoint(record.hash, record.rev_index);
+    }
+}
+
+impl VerificationReport {
+    // An empty ledger is reported as such up front.
+    pub(crate) fn new(records: usize) -> Self {
+        let mut report: VerificationReport = Self { records, errors: Vec::new() };
+        if records == 0 {
+            report.errors.push(VerificationError {
+                index: None,
+                kind: VerificationErrorKind::EmptyInput,
+            });
+        }
+        return report;
+    }
+
+    // Records a failure, returning whether verification should go on.
+    pub(crate) fn push(&mut self, index: usize, kind: VerificationErrorKind, mode: VerifyMode) -> bool {
+        self.errors.push(VerificationError { index: Some(index), kind });
+        return mode == VerifyMode::CollectAll;
+    }
+
+    // Checks the record at `index` against the position before it, returning whether verification should go on.
+    pub(crate) fn check_record<H: ChainHasher>(&mut self, hasher: &H, config: &ClockConfig, from: &Cursor, index: usize, record: &Record, mode: VerifyMode) -> bool {
+        let errors: Vec<VerificationErrorKind> = from.errors(record, config.hashes_per_rev, &config.schedule());
+        // An invalid hash count leaves nothing sensible to re-hash.
+        let counted: bool = !errors.iter().any(|kind| matches!(kind, VerificationErrorKind::HashCount { .. }));
+        for kind in errors {
+            if !self.push(index, kind, mode) {
+                return false;
+            }
+        }
+
+        if counted {
+            let expected: Hash = record.expected_hash(hasher, &from.hash, config.hashes_per_rev);
+            if expected != record.hash {
+                return self.push(index, VerificationErrorKind::HashMismatch { expected, actual: record.hash }, mode);
+            }
+        }
+        return true;
+    }
+
+    pub fn is_valid(&self) -> bool {
+        return self.errors.is_empty();
+    }
+
+    pub fn first_error(&self) -> Option<&VerificationError> {
+        return self.errors.first();
+    }
+
+    pub fn into_result(self) -> Result<(), VerificationError> {
+        return match self.errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        };
+    }
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if let Some(index) = self.index {
+            write!(f, "Record {}: ", index)?;
+        }
+        return match &self.kind {
+            VerificationErrorKind::EmptyInput => write!(f, "No records to verify."),
+            VerificationErrorKind::HashMismatch { expected, actual } => write!(f, "Hash mismatch, expected {}, got {}.", expected, actual),
+            VerificationErrorKind::RevGap { expected, actual } => write!(f, "Rev index {} does not follow, expected {}.", actual, expected),
+            VerificationErrorKind::PhaseIndex { expected, actual } => write!(f, "Phase index {}, expected {}.", actual, expected),
+            VerificationErrorKind::CycleIndex { expected, actual } => write!(f, "Cycle index {}, expected {}.", actual, expected),
+            VerificationErrorKind::HashCount { remaining, actual } => write!(f, "Entry spends {} hashes, its rev has {} left.", actual, remaining),
+            VerificationErrorKind::TimestampDrift {
+                expected_ms,
+                actual_ms,
+                allowed_ms,
+            } => write!(
+                f,
+                "Timestamp {}ms drifts from the expected {}ms by more than {}ms.",
+                actual_ms, expected_ms, allowed_ms
+            ),
+            VerificationErrorKind::TimestampBackwards { previous_ms, actual_ms } => {
+                write!(f, "Timestamp {}ms goes back from the previous {}ms.", actual_ms, previous_ms)
+            }
+            VerificationErrorKind::UnanchoredRun => write!(f, "Tick run has nothing before it to hash from."),
+            VerificationErrorKind::Ledger(error) => write!(f, "{}", error),
+        };
+    }
+}
+
+impl Error for VerificationError {}
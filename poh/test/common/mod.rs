@@ -0,0 +1,26 @@
+// Fixtures shared by the integration tests.
+
+use poh::types::{Anchor, PoH, Record};
+
+use lib::{
+    clock::Clock,
+    hash::Hasher,
+    metronome::ClockConfig,
+    profile::{Profile, ProfileConfig},
+};
+
+pub fn test_profile() -> ProfileConfig {
+    return ProfileConfig::named(Profile::Test).unwrap();
+}
+
+pub fn generator<C: Clock>(seed: &[u8], profile: &ProfileConfig, clock: C) -> PoH<Hasher, C> {
+    return PoH::with_clock(seed, Hasher::default(), profile, clock).unwrap();
+}
+
+pub fn verify(seed: &[u8], config: &ClockConfig, records: &[Record]) {
+    let hasher: Hasher = Hasher::default();
+    assert!(
+        PoH::verify_anchored(&hasher, config, &Anchor::genesis(&hasher, seed), records).is_ok(),
+        "Records should verify from the seed."
+    );
+}
@@ -0,0 +1,186 @@
+#[cfg(test)]
+mod compaction {
+    use poh::types::{Anchor, CompactEntry, PoH, Record, TickRun, VerificationError, VerificationErrorKind, VerifyMode};
+
+    use lib::{
+        clock::{Clock, FastForwardClock, ManualClock},
+        hash::Hasher,
+        metronome::ClockConfig,
+        profile::{Profile, ProfileConfig},
+    };
+
+    const SEED: &[u8] = b"compaction";
+
+    fn config() -> ClockConfig {
+        return Profile::Test.clock().unwrap();
+    }
+
+    // Mostly ticks, with events, mid-rev entries and a few irregular timestamps.
+    fn ledger() -> Vec<Record> {
+        let clock: ManualClock = ManualClock::new();
+        let mut poh: PoH<Hasher, ManualClock> = PoH::with_clock(SEED, Hasher::default(), &ProfileConfig::named(Profile::Test).unwrap(), clock.clone()).unwrap();
+        let mut records: Vec<Record> = Vec::new();
+
+        for rev in 0..120u64 {
+            clock.advance(1_000);
+            match rev {
+                30 => records.push(poh.insert_event(b"event")),
+                50 => {
+                    records.push(poh.insert_events(&[b"mid", b"rev"]));
+                    records.push(poh.next_rev());
+                }
+                // A stall off the run's schedule, then time going backwards.
+                70 => {
+                    clock.advance(1_000_000);
+                    records.push(poh.next_rev());
+                }
+                90 => {
+                    clock.set(clock.now_us().saturating_sub(5_000));
+                    records.push(poh.next_rev());
+                }
+                _ => records.push(poh.next_rev()),
+            }
+        }
+        return records;
+    }
+
+    fn runs(entries: &[CompactEntry]) -> Vec<(u64, u64)> {
+        return entries
+            .iter()
+            .filter_map(|entry| match entry {
+                CompactEntry::Ticks(run) => Some((run.start_rev, run.count)),
+                CompactEntry::Record(_) => None,
+            })
+            .collect();
+    }
+
+    #[test]
+    fn collapses_tick_runs_losslessly() {
+        let records: Vec<Record> = ledger();
+        let entries: Vec<CompactEntry> = PoH::compact_records(&config(), &records);
+
+        assert!(matches!(entries[0], CompactEntry::Record(_)), "The first record should be kept as is.");
+        assert_eq!(
+            runs(&entries),
+            vec![(1, 29), (31, 19), (51, 19), (70, 20), (90, 30)],
+            "Runs should break at events, mid-rev entries and irregular timestamps."
+        );
+        assert_eq!(entries.len(), 9, "Events and rev-splitting entries should stay full records.");
+
+        let expanded: Vec<Record> = PoH::expand_records(&Hasher::default(), &config(), None, &entries).unwrap();
+        assert_eq!(expanded, records, "Expansion should restore every record.");
+    }
+
+    #[test]
+    fn verifies_without_expanding() {
+        let records: Vec<Record> = ledger();
+        let entries: Vec<CompactEntry> = PoH::compact_records(&config(), &records);
+        let hasher: Hasher = Hasher::default();
+
+        assert!(PoH::verify_compact(&hasher, &config(), &entries).is_ok(), "A compacted valid ledger should verify.");
+        assert!(
+            PoH::compact_report(&hasher, &config(), Some(&Anchor::genesis(&hasher, SEED)), &entries, VerifyMode::StopAtFirst).is_valid(),
+            "It should verify from genesis too."
+        );
+
+        let mut forged: Vec<CompactEntry> = entries.clone();
+        if let CompactEntry::Ticks(run) = &mut forged[1] {
+            run.hash.as_mut()[0] ^= 1;
+        }
+        let error: VerificationError = PoH::verify_compact(&hasher, &config(), &forged).unwrap_err();
+        assert_eq!(error.index, Some(1), "A forged run should be reported by entry index.");
+        assert!(
+            matches!(error.kind, VerificationErrorKind::HashMismatch { .. }),
+            "A forged run hash should not match the chain."
+        );
+        assert!(
+            PoH::verify_records(&PoH::expand_records(&hasher, &config(), None, &forged).unwrap()).is_err(),
+            "A forged run should expand to records that fail too."
+        );
+
+        forged = entries.clone();
+        if let CompactEntry::Ticks(run) = &mut forged[3] {
+            run.count += 1;
+        }
+        assert_eq!(
+            PoH::verify_compact(&hasher, &config(), &forged).unwrap_err().index,
+            Some(3),
+            "A run claiming an extra tick should not match the chain."
+        );
+
+        forged = entries;
+        forged.remove(0);
+        assert_eq!(
+            PoH::verify_compact(&hasher, &config(), &forged).unwrap_err().kind,
+            VerificationErrorKind::UnanchoredRun,
+            "A leading run needs something to hash from."
+        );
+        assert!(
+            PoH::expand_records(&hasher, &config(), None, &forged).is_err(),
+            "A leading run cannot be expanded without an anchor."
+        );
+    }
+
+    #[test]
+    fn run_accessors() {
+        let run: TickRun = TickRun {
+            start_rev: 10,
+            count: 4,
+            hash: Default::default(),
+            start_us: 2_700,
+        };
+        assert_eq!(run.next_rev(), 14, "The next rev should follow the last tick.");
+        assert_eq!(
+            (0..4).map(|offset| run.timestamp_ms(offset, 1_250)).collect::<Vec<u64>>(),
+            vec![2, 3, 5, 6],
+            "Ticks should be stamped one rev apart from the start time."
+        );
+    }
+
+    #[test]
+    fn long_runs_stay_fixed_size() {
+        // Revs of 1.25 ms, so consecutive ticks are not a whole number of milliseconds apart.
+        let profile: ProfileConfig = ProfileConfig::custom(ClockConfig {
+            revs_per_second: 800,
+            us_per_rev: 1_250,
+            ..config()
+        })
+        .unwrap();
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(SEED, Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+        let records: Vec<Record> = (0..10_000).map(|_| poh.next_rev()).collect();
+        let entries: Vec<CompactEntry> = PoH::compact_records(&profile.clock, &records);
+
+        assert_eq!(entries.len(), 2, "Every tick after the first should join a single run.");
+        assert_eq!(
+            entries[1],
+            CompactEntry::Ticks(TickRun {
+                start_rev: 1,
+                count: 9_999,
+                hash: records[9_999].hash,
+                start_us: 2_500,
+            }),
+            "The run should hold only its count, last hash and start time."
+        );
+        assert_eq!(
+            PoH::expand_records(&Hasher::default(), &profile.clock, None, &entries).unwrap(),
+            records,
+            "Expansion should restore every timestamp."
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn compacted_json_is_smaller() {
+        let records: Vec<Record> = ledger();
+        let entries: Vec<CompactEntry> = PoH::compact_records(&config(), &records);
+        let full: String = serde_json::to_string(&records).unwrap();
+        let compact: String = serde_json::to_string(&entries).unwrap();
+
+        assert!(compact.len() * 4 < full.len(), "Compaction should shrink a tick-heavy ledger several times.");
+        assert_eq!(
+            serde_json::from_str::<Vec<CompactEntry>>(&compact).unwrap(),
+            entries,
+            "Compacted entries should round-trip through JSON."
+        );
+    }
+}
@@ -0,0 +1,162 @@
+#[cfg(test)]
+mod record_encoding {
+    use poh::types::{DecodeError, PoH, RECORD_HEADER_BYTES, RECORD_VERSION, Record, RecordRef};
+
+    use lib::{
+        clock::FastForwardClock,
+        hash::{Hash, Hasher},
+        profile::{Profile, ProfileConfig},
+    };
+
+    // Whole-rev ticks and events, and mid-rev entries with explicit hash counts.
+    fn records() -> Vec<Record> {
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"encoding", Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+        return vec![
+            poh.next_rev(),
+            poh.insert_event(b"whole rev"),
+            poh.insert_events(&[b"first", b"", b"third"]),
+            poh.insert_events(&[]),
+            poh.next_rev(),
+        ];
+    }
+
+    #[test]
+    fn round_trips_records() {
+        let records: Vec<Record> = records();
+        let mut bytes: Vec<u8> = Vec::new();
+        for record in &records {
+            record.encode(&mut bytes).unwrap();
+        }
+        assert_eq!(bytes.len(), records.iter().map(Record::encoded_len).sum::<usize>(), "Encoded length should be exact.");
+
+        let mut decoded: Vec<Record> = Vec::new();
+        let mut offset: usize = 0;
+        while offset < bytes.len() {
+            let (record, len): (Record, usize) = Record::decode(&bytes[offset..]).unwrap();
+            decoded.push(record);
+            offset += len;
+        }
+        assert_eq!(decoded, records, "Records should survive a binary round trip.");
+        assert!(
+            PoH::verify_records_with_config(&Hasher::default(), &Profile::Test.clock().unwrap(), &decoded).is_ok(),
+            "Decoded records should verify."
+        );
+    }
+
+    #[test]
+    fn fixed_little_endian_layout() {
+        let record: Record = Record {
+            hash: Hash::new([7u8; 32]),
+            rev_index: 0x0102,
+            phase_index: 3,
+            cycle_index: 4,
+            timestamp_ms: 5,
+            num_hashes: Some(6),
+            events: vec![b"abc".to_vec()],
+        };
+        let bytes: Vec<u8> = record.to_bytes().unwrap();
+
+        assert_eq!(bytes.len(), RECORD_HEADER_BYTES + 4 + 3, "Events should be length-prefixed after the header.");
+        assert_eq!(bytes[..2], [RECORD_VERSION, 1], "Version and flags should lead.");
+        assert_eq!(bytes[2..34], [7u8; 32], "The hash should follow as raw bytes.");
+        assert_eq!(bytes[34..42], 0x0102u64.to_le_bytes(), "Integers should be little-endian.");
+        assert_eq!(bytes[66..74], 6u64.to_le_bytes(), "The hash count should follow the timestamp.");
+        assert_eq!(
+            bytes[74..],
+            [1, 0, 0, 0, 3, 0, 0, 0, b'a', b'b', b'c'],
+            "Events should be counted, then each length-prefixed."
+        );
+
+        let whole: Record = Record { num_hashes: None, ..record };
+        assert_eq!(whole.to_bytes().unwrap()[1], 0, "An implicit hash count should clear the flag.");
+        assert_eq!(whole.to_bytes().unwrap()[66..74], [0u8; 8], "An implicit hash count should encode as zero.");
+    }
+
+    #[test]
+    fn decodes_events_in_place() {
+        let record: Record = records().swap_remove(2);
+        let bytes: Vec<u8> = record.to_bytes().unwrap();
+        let (decoded, len): (RecordRef<'_>, usize) = RecordRef::decode(&bytes).unwrap();
+
+        assert_eq!(len, bytes.len(), "The whole encoding should be consumed.");
+        assert_eq!(decoded.events.len(), 3, "Every event should be listed.");
+        let events: Vec<&[u8]> = decoded.events.clone().collect();
+        assert_eq!(events, vec![&b"first"[..], b"", b"third"], "Events should decode in order.");
+        assert!(
+            events.iter().all(|event| bytes.as_ptr_range().contains(&event.as_ptr()) || event.is_empty()),
+            "Events should borrow from the input."
+        );
+        assert_eq!(decoded.to_record(), record, "An owned copy should match the original.");
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        let bytes: Vec<u8> = records().swap_remove(2).to_bytes().unwrap();
+
+        for len in 0..bytes.len() {
+            assert!(
+                matches!(RecordRef::decode(&bytes[..len]), Err(DecodeError::Truncated { .. })),
+                "A record cut to {} bytes should be reported as truncated.",
+                len
+            );
+        }
+
+        let mut forged: Vec<u8> = bytes.clone();
+        forged[0] = RECORD_VERSION + 1;
+        assert_eq!(
+            RecordRef::decode(&forged).unwrap_err(),
+            DecodeError::UnknownVersion(RECORD_VERSION + 1),
+            "Unknown versions should be rejected."
+        );
+
+        forged = bytes.clone();
+        forged[1] = 0x81;
+        assert_eq!(
+            RecordRef::decode(&forged).unwrap_err(),
+            DecodeError::UnknownFlags(0x81),
+            "Unknown flags should be rejected."
+        );
+
+        forged = bytes.clone();
+        forged[1] = 0;
+        forged[66..74].copy_from_slice(&7u64.to_le_bytes());
+        assert_eq!(
+            RecordRef::decode(&forged).unwrap_err(),
+            DecodeError::UnflaggedHashCount(7),
+            "A hash count without its flag should be rejected, so every record has one encoding."
+        );
+
+        forged = bytes;
+        forged[78..82].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(
+            matches!(RecordRef::decode(&forged), Err(DecodeError::Truncated { .. })),
+            "Oversized event lengths should be rejected."
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn json_and_binary_agree() {
+        let legacy: &str =
+            r#"{"hash":"0101010101010101010101010101010101010101010101010101010101010101","rev_index":1,"phase_index":0,"cycle_index":0,"timestamp_ms":6}"#;
+        let mut json: Vec<String> = records().iter().map(|record| serde_json::to_string(record).unwrap()).collect();
+        json.push(legacy.to_string());
+
+        for text in json {
+            let record: Record = serde_json::from_str(&text).unwrap();
+            let bytes: Vec<u8> = record.to_bytes().unwrap();
+            let (decoded, _): (Record, usize) = Record::decode(&bytes).unwrap();
+
+            assert_eq!(serde_json::to_string(&decoded).unwrap(), text, "JSON should survive a trip through binary.");
+            assert!(bytes.len() < text.len(), "Binary should be smaller than JSON.");
+        }
+
+        // A legacy single event is kept through binary, then written as an event list.
+        let legacy_event: String = legacy.replace("}", r#","event":[101,118]}"#);
+        let record: Record = serde_json::from_str(&legacy_event).unwrap();
+        let (decoded, _): (Record, usize) = Record::decode(&record.to_bytes().unwrap()).unwrap();
+        assert_eq!(decoded.events, vec![b"ev".to_vec()], "A legacy event should survive a trip through binary.");
+        assert_eq!(decoded, record, "Binary should keep every field of a legacy record.");
+    }
+}
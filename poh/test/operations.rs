@@ -5,13 +5,17 @@ mod poh_operations {
         time::{Duration, Instant},
     };
 
-    use poh::types::{PoH, Record};
+    use poh::types::{Anchor, LedgerError, LedgerMeta, PoH, Record, SampleConfig, VerificationError, VerificationErrorKind};
 
     use thread::native_runtime::types::{Config, JoinHandle, Native};
 
     use lib::{
-        hash::Hasher,
-        metronome::{DEFAULT_BATCH_SIZE, DEFAULT_CHANNEL_CAPACITY, DEFAULT_HASHES_PER_REV, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE, DEFAULT_US_PER_REV},
+        clock::FastForwardClock,
+        hash::{Algorithm, ChainHasher, Hash, HashMode, Hasher},
+        metronome::{
+            ClockConfig, DEFAULT_BATCH_SIZE, DEFAULT_CHANNEL_CAPACITY, DEFAULT_HASHES_PER_REV, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE, DEFAULT_US_PER_REV,
+        },
+        profile::{Profile, ProfileConfig},
     };
 
     #[test]
@@ -35,15 +39,15 @@ mod poh_operations {
     #[test]
     fn hash_chain_extension() {
         let hasher: Hasher = Hasher::default();
-        let seed: [u8; 32] = [1u8; 32]; // Some seed data.
+        let seed: Hash = Hash::new([1u8; 32]); // Some seed data.
         let iterations: u64 = 10;
         // Test hash chain extension.
-        let result: [u8; 32] = hasher.extend_hash_chain(&seed, iterations);
+        let result: Hash = hasher.extend_hash_chain(&seed, iterations);
         // Verify by manually applying hash iterations.
-        let mut expected: [u8; 32] = seed;
+        let mut expected: Hash = seed;
 
         for _ in 0..iterations {
-            expected = hasher.hash(&expected);
+            expected = hasher.hash(expected.as_bytes());
         }
 
         assert_eq!(result, expected, "Hash chain extension produced incorrect result.");
@@ -52,11 +56,11 @@ mod poh_operations {
     #[test]
     fn hash_chain_verification() {
         let hasher: Hasher = Hasher::default();
-        let seed: [u8; 32] = [1u8; 32]; // Initial hash.
+        let seed: Hash = Hash::new([1u8; 32]); // Initial hash.
         let iterations: u64 = DEFAULT_HASHES_PER_REV;
         let event_data: &'static [u8; 10] = b"Test event";
         // Create a valid hash chain with event.
-        let mut current_hash: [u8; 32] = hasher.embed_data(&seed, event_data);
+        let mut current_hash: Hash = hasher.embed_data(&seed, event_data);
 
         current_hash = hasher.extend_hash_chain(&current_hash, iterations);
         // Verify the valid hash chain.
@@ -66,8 +70,8 @@ mod poh_operations {
         );
 
         // Modify hash and ensure verification fails.
-        let mut bad_hash: [u8; 32] = current_hash;
-        bad_hash[0] ^= 0xFF; // Corrupt the hash.
+        let mut bad_hash: Hash = current_hash;
+        bad_hash.as_mut()[0] ^= 0xFF; // Corrupt the hash.
 
         assert!(
             !hasher.verify_hash_chain(&seed, &bad_hash, iterations, Some(event_data)),
@@ -87,14 +91,13 @@ mod poh_operations {
         let rev3: Record = poh.next_rev(); // Normal rev.
 
         // Check that event was stored.
-        assert!(rev2.event.is_some());
-        assert_eq!(rev2.event.clone().unwrap(), event_data.as_bytes());
+        assert_eq!(rev2.events, vec![event_data.as_bytes().to_vec()]);
         // Check that non-event revs don't have events.
-        assert!(rev1.event.is_none());
-        assert!(rev3.event.is_none());
+        assert!(rev1.events.is_empty());
+        assert!(rev3.events.is_empty());
         // Verify hash chain integrity across all revs.
         let records: Vec<Record> = vec![rev1, rev2, rev3];
-        assert!(PoH::verify_records(&records), "Records with event failed verification.");
+        assert!(PoH::verify_records(&records).is_ok(), "Records with event failed verification.");
     }
 
     #[test]
@@ -118,7 +121,7 @@ mod poh_operations {
         // The first rev of phase 1 should be at index DEFAULT_REVS_PER_PHASE.
         assert_eq!(first_rev.phase_index, 1, "First rev of phase 1 has incorrect phase_index.");
         // Verify hash chain integrity across phase boundary.
-        assert!(PoH::verify_records(&records), "Records across phase boundary failed verification.");
+        assert!(PoH::verify_records(&records).is_ok(), "Records across phase boundary failed verification.");
     }
 
     #[test]
@@ -168,36 +171,36 @@ mod poh_operations {
         }
 
         // Verify original records are valid.
-        assert!(PoH::verify_records(&records), "Valid records failed verification.");
+        assert!(PoH::verify_records(&records).is_ok(), "Valid records failed verification.");
         // Test various corruption scenarios
         let mut corrupted: Vec<Record> = records.clone();
         // 1. Corrupt a hash.
-        corrupted[5].hash[0] ^= 0xFF;
-        assert!(!PoH::verify_records(&corrupted), "Failed to detect hash corruption.");
+        corrupted[5].hash.as_mut()[0] ^= 0xFF;
+        assert!(PoH::verify_records(&corrupted).is_err(), "Failed to detect hash corruption.");
         // 2. Corrupt rev index.
         corrupted = records.clone();
         corrupted[3].rev_index += 2; // Skip a rev index.
-        assert!(!PoH::verify_records(&corrupted), "Failed to detect rev index corruption.");
+        assert!(PoH::verify_records(&corrupted).is_err(), "Failed to detect rev index corruption.");
         // 3. Corrupt phase index.
         corrupted = records.clone();
         corrupted[4].phase_index += 1; // Incorrect phase index.
-        assert!(!PoH::verify_records(&corrupted), "Failed to detect phase index corruption.");
+        assert!(PoH::verify_records(&corrupted).is_err(), "Failed to detect phase index corruption.");
         // 4. Corrupt cycle.
         corrupted = records.clone();
         corrupted[5].cycle_index += 1; // Incorrect cycle.
-        assert!(!PoH::verify_records(&corrupted), "Failed to detect cycle corruption.");
+        assert!(PoH::verify_records(&corrupted).is_err(), "Failed to detect cycle corruption.");
     }
 
     #[test]
     fn constant_time_eq() {
         let hasher: Hasher = Hasher::default();
         // Can't test the actual constant-time property, but can test correctness.
-        let hash1: [u8; 32] = [0u8; 32];
-        let hash2: [u8; 32] = [0u8; 32];
-        let hash3: [u8; 32] = {
+        let hash1: Hash = Hash::new([0u8; 32]);
+        let hash2: Hash = Hash::new([0u8; 32]);
+        let hash3: Hash = {
             let mut h: [u8; 32] = [0u8; 32];
             h[31] = 1; // Differs at the last byte.
-            h
+            Hash::new(h)
         };
 
         // Test the function through verify_hash_chain which uses constant_time_eq.
@@ -300,7 +303,7 @@ mod poh_operations {
             );
         }
 
-        assert!(PoH::verify_records(&records_received), "PoH integrity check failed.");
+        assert!(PoH::verify_records(&records_received).is_ok(), "PoH integrity check failed.");
 
         for i in 1..records_received.len() {
             let prev: &Record = &records_received[i - 1];
@@ -316,6 +319,177 @@ mod poh_operations {
         }
     }
 
+    #[test]
+    fn custom_hasher_backend() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let mut poh: PoH<Hasher> = PoH::with_hasher(&seed, Hasher::new(Algorithm::BLAKE3));
+        let records: Vec<Record> = vec![poh.next_rev(), poh.insert_event(b"Backend event"), poh.next_rev()];
+
+        assert!(
+            PoH::verify_records_with(&Hasher::new(Algorithm::BLAKE3), &records).is_ok(),
+            "Records should verify with the backend that produced them."
+        );
+        assert!(PoH::verify_records(&records).is_err(), "Records should not verify with the default backend.");
+    }
+
+    #[test]
+    fn domain_separated_ledger() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let hasher: Hasher = Hasher::with_mode(Algorithm::SHA256, HashMode::DomainSeparatedV1);
+        let mut poh: PoH = PoH::with_hasher(&seed, hasher);
+        let records: Vec<Record> = vec![poh.next_rev(), poh.insert_event(&[]), poh.next_rev()];
+
+        assert!(
+            PoH::verify_records_with(&hasher, &records).is_ok(),
+            "Domain-separated records should verify in their mode."
+        );
+        assert!(PoH::verify_records(&records).is_err(), "Domain-separated records should not verify as legacy.");
+    }
+
+    #[test]
+    fn custom_clock_config() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let config: ClockConfig = ClockConfig {
+            revs_per_second: 1_000,
+            us_per_rev: 1_000,
+            hashes_per_rev: 100,
+            revs_per_phase: 4,
+            phases_per_cycle: 2,
+            ..ClockConfig::default()
+        };
+        let mut poh: PoH = PoH::with_config(&seed, Hasher::default(), config).unwrap();
+        let records: Vec<Record> = (0..10).map(|i| if i == 5 { poh.insert_event(b"Config event") } else { poh.next_rev() }).collect();
+
+        assert_eq!(records[9].phase_index, 2, "Phases should follow the configured revs per phase.");
+        assert_eq!(records[9].cycle_index, 1, "Cycles should follow the configured phases per cycle.");
+        assert!(
+            PoH::verify_records_with_config(&Hasher::default(), &config, &records).is_ok(),
+            "Records should verify with the config that produced them."
+        );
+        assert!(PoH::verify_records(&records).is_err(), "Records should not verify with the default config.");
+
+        let invalid: ClockConfig = ClockConfig { us_per_rev: 6_250, ..config };
+        assert!(PoH::with_config(&seed, Hasher::default(), invalid).is_err(), "Invalid configs should be rejected.");
+    }
+
+    #[test]
+    fn profile_recorded_with_ledger() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let test: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let mut poh: PoH = PoH::with_profile(&seed, Hasher::default(), &test).unwrap();
+        let start: Instant = Instant::now();
+        let records: Vec<Record> = (0..1_000).map(|_| poh.next_rev()).collect();
+
+        assert!(start.elapsed() < Duration::from_secs(1), "The test profile should not pace revs.");
+
+        let meta: LedgerMeta = poh.ledger_meta();
+        assert_eq!(meta.profile, test, "Metadata should record the profile.");
+        assert_eq!(PoH::verify_ledger(&meta, &test, &records), Ok(()), "Ledger should verify under its own profile.");
+
+        let production: ProfileConfig = ProfileConfig::named(Profile::Production).unwrap();
+        assert_eq!(
+            PoH::verify_ledger(&meta, &production, &records),
+            Err(VerificationError {
+                index: None,
+                kind: VerificationErrorKind::Ledger(LedgerError::ProfileMismatch {
+                    expected: Profile::Production,
+                    actual: Profile::Test,
+                }),
+            }),
+            "Ledger should not verify under another profile."
+        );
+
+        let json: String = serde_json::to_string(&meta).unwrap();
+        assert_eq!(serde_json::from_str::<LedgerMeta>(&json).unwrap(), meta, "Metadata should round-trip through JSON.");
+    }
+
+    #[test]
+    fn algorithm_recorded_with_ledger() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let test: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let mut poh: PoH = PoH::with_algorithm(&seed, Algorithm::BLAKE3);
+        poh.profile = test.profile;
+        poh.config = test.clock;
+        let records: Vec<Record> = vec![poh.next_rev(), poh.insert_event(b"Algorithm event"), poh.next_rev()];
+        let meta: LedgerMeta = poh.ledger_meta();
+
+        assert_eq!(meta.algorithm, Algorithm::BLAKE3, "Metadata should record the algorithm.");
+        assert_eq!(
+            PoH::verify_ledger(&meta, &test, &records),
+            Ok(()),
+            "Ledger should verify with its recorded algorithm."
+        );
+        assert_eq!(
+            PoH::verify_ledger_with(&Hasher::new(Algorithm::SHA256), &meta, &test, &records),
+            Err(VerificationError {
+                index: None,
+                kind: VerificationErrorKind::Ledger(LedgerError::AlgorithmMismatch {
+                    expected: Algorithm::SHA256,
+                    actual: Algorithm::BLAKE3,
+                }),
+            }),
+            "Verifying BLAKE3 records with SHA-256 should report the mismatch."
+        );
+        assert_eq!(
+            PoH::verify_ledger_with(&Hasher::with_mode(Algorithm::BLAKE3, HashMode::DomainSeparatedV1), &meta, &test, &records),
+            Err(VerificationError {
+                index: None,
+                kind: VerificationErrorKind::Ledger(LedgerError::ModeMismatch {
+                    expected: HashMode::DomainSeparatedV1,
+                    actual: HashMode::Legacy,
+                }),
+            }),
+            "A different hash mode should be reported too."
+        );
+
+        let json: String = serde_json::to_string(&meta).unwrap();
+        assert!(json.contains(r#""algorithm":"BLAKE3""#), "Algorithm should be stored by name: {}", json);
+        assert_eq!(serde_json::from_str::<LedgerMeta>(&json).unwrap(), meta, "Metadata should round-trip through JSON.");
+    }
+
+    #[test]
+    fn warmup_cycle_indices() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let config: ClockConfig = ClockConfig {
+            warmup: true,
+            phases_per_cycle: 64,
+            ..Profile::Test.clock().unwrap()
+        };
+        let mut poh: PoH = PoH::with_config(&seed, Hasher::default(), config).unwrap();
+        let records: Vec<Record> = (0..200).map(|_| poh.next_rev()).collect();
+
+        // The first warm-up cycle has 32 phases of 4 revs.
+        assert_eq!(records[127].cycle_index, 0, "Rev 127 should close the first warm-up cycle.");
+        assert_eq!(records[128].cycle_index, 1, "Rev 128 should open the second cycle.");
+        assert_eq!((poh.cycle_count, poh.phase_count), (1, 18), "Generator should track the next rev's position.");
+        assert!(
+            PoH::verify_records_with_config(&Hasher::default(), &config, &records).is_ok(),
+            "Warm-up ledger should verify with its schedule."
+        );
+        assert!(
+            PoH::verify_records_with_config(&Hasher::default(), &ClockConfig { warmup: false, ..config }, &records).is_err(),
+            "Warm-up ledger should not verify without warm-up."
+        );
+    }
+
+    #[test]
+    fn generated_ledger_anchors_to_seed() {
+        let seed: [u8; 64] = [b'0'; 64];
+        let test: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let hasher: Hasher = Hasher::with_mode(Algorithm::SHA256, HashMode::DomainSeparatedV1);
+        let mut poh: PoH = PoH::with_profile(&seed, hasher, &test).unwrap();
+        let records: Vec<Record> = vec![poh.insert_event(b"First event"), poh.next_rev(), poh.next_rev()];
+
+        assert!(
+            PoH::verify_anchored(&hasher, &test.clock, &Anchor::genesis(&hasher, &seed), &records).is_ok(),
+            "Generated records should verify from the genesis of their seed."
+        );
+        assert!(
+            PoH::verify_anchored(&hasher, &test.clock, &Anchor::genesis(&hasher, b"other"), &records).is_err(),
+            "Generated records should not verify from another seed."
+        );
+    }
+
     #[test]
     fn hash_rate_constant() {
         // Verify that DEFAULT_HASHES_PER_REV = 12500 as specified in requirements.
@@ -350,4 +524,70 @@ mod poh_operations {
 
         assert!((days_per_cycle - 2.0).abs() < 0.001, "1 cycle should be approximately 2 days.");
     }
+
+    #[test]
+    fn record_json_wire_format() {
+        let mut poh: PoH = PoH::new(b"wire format");
+        let record: Record = poh.insert_event(b"event");
+        let json: String = serde_json::to_string(&record).unwrap();
+
+        assert!(
+            json.contains(&format!("\"hash\":\"{}\"", record.hash.to_hex())),
+            "Record hash should serialize as hex."
+        );
+
+        // A record written before the typed hash must still parse.
+        let legacy: &str =
+            r#"{"hash":"0101010101010101010101010101010101010101010101010101010101010101","rev_index":1,"phase_index":0,"cycle_index":0,"timestamp_ms":6}"#;
+        let parsed: Record = serde_json::from_str(legacy).unwrap();
+
+        assert_eq!(parsed.hash, Hash::new([1u8; 32]), "Legacy hex hash should parse.");
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), legacy, "Re-serialized record should be byte-identical.");
+
+        // A record written before entries carried several events keeps its single one.
+        let legacy_event: &str = r#"{"hash":"0101010101010101010101010101010101010101010101010101010101010101","rev_index":1,"phase_index":0,"cycle_index":0,"timestamp_ms":6,"event":[101,118]}"#;
+        let parsed: Record = serde_json::from_str(legacy_event).unwrap();
+        assert_eq!(parsed.events, vec![b"ev".to_vec()], "A legacy event should become the only event.");
+        assert!(
+            serde_json::to_string(&parsed).unwrap().ends_with(r#""events":[[101,118]]}"#),
+            "A legacy event should be written back as an event list."
+        );
+        assert!(
+            serde_json::from_str::<Record>(&legacy_event.replace("}", r#","events":[[1]]}"#)).is_err(),
+            "A record with both event fields should be rejected."
+        );
+    }
+
+    #[test]
+    fn interleaves_event_entries() {
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"entries", Hasher::default(), &ProfileConfig::default(), FastForwardClock::new()).unwrap();
+
+        let mut records: Vec<Record> = vec![poh.next_rev()];
+        records.push(poh.insert_events(&[b"first", b"second"]));
+        records.extend((0..100u8).map(|i| poh.insert_events(&[&[i]])));
+        records.push(poh.next_rev());
+        records.push(poh.insert_event(b"whole rev"));
+
+        let counts: Vec<Option<u64>> = records.iter().map(|record| record.num_hashes).collect();
+        let revs: Vec<u64> = records.iter().map(|record| record.rev_index).collect();
+        assert_eq!(counts[0], None, "A plain tick should keep its hash count implicit.");
+        assert!(counts[1..102].iter().all(|count| *count == Some(1)), "Event entries should spend one hash each.");
+        assert_eq!(counts[102], Some(DEFAULT_HASHES_PER_REV - 101), "The tick should spend the rest of the rev.");
+        assert_eq!(counts[103], None, "A whole-rev event should keep its hash count implicit.");
+        assert_eq!(revs[1..103], [1; 102], "Entries up to the tick should share its rev.");
+        assert_eq!(revs[103], 2, "The next entry should start a new rev.");
+        assert_eq!(records[1].events.len(), 2, "Both events should be in one entry.");
+        assert_eq!(poh.rev_count, 3, "Only the rev-ending entries should count as revs.");
+
+        assert!(
+            PoH::verify_from_seed(b"entries", &records).is_ok(),
+            "Interleaved entries should verify from the seed."
+        );
+        assert!(PoH::verify_records(&records[50..]).is_ok(), "A ledger starting mid-rev should verify.");
+        assert!(PoH::verify_timestamps(&records).is_ok(), "Mid-rev entries should carry their rev's time.");
+        assert!(
+            PoH::sample_records(&records, SampleConfig::default()).report().valid,
+            "Sampled verification should follow variable hash counts."
+        );
+    }
 }
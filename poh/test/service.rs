@@ -0,0 +1,116 @@
+#[cfg(test)]
+mod common;
+
+#[cfg(test)]
+mod poh_service {
+    use std::{
+        sync::mpsc::TrySendError,
+        thread::{self, JoinHandle},
+    };
+
+    use poh::types::{EventSender, PoH, PoHService, Record, ServiceConfig};
+
+    use crate::common::{generator, test_profile, verify};
+
+    use lib::{clock::FastForwardClock, hash::Hasher};
+
+    #[test]
+    fn records_submitted_events_in_batches() {
+        let config: ServiceConfig = ServiceConfig {
+            batch_size: 16,
+            ..ServiceConfig::default()
+        };
+        let service: PoHService<Hasher, FastForwardClock> = PoHService::spawn(generator(b"service", &test_profile(), FastForwardClock::new()), &config).unwrap();
+        let sender: EventSender = service.sender();
+
+        for i in 0..500u32 {
+            sender.submit(i.to_le_bytes().to_vec()).unwrap();
+        }
+
+        let mut records: Vec<Record> = Vec::new();
+        for _ in 0..4 {
+            let batch: Vec<Record> = service.records().recv().unwrap();
+            assert_eq!(batch.len(), 16, "Batches should be full while running.");
+            records.extend(batch);
+        }
+        let (poh, rest): (PoH<Hasher, FastForwardClock>, Vec<Record>) = service.shutdown().unwrap();
+        records.extend(rest);
+
+        let events: Vec<Vec<u8>> = records.iter().flat_map(|record| record.events.clone()).collect();
+        let expected: Vec<Vec<u8>> = (0..500u32).map(|i| i.to_le_bytes().to_vec()).collect();
+        assert_eq!(events, expected, "Every event should be recorded once, in order.");
+        assert_eq!(poh.rev_hashes, 0, "The service should stop on a rev boundary.");
+        assert_eq!(records.last().unwrap().rev_index + 1, poh.rev_count, "The last record should end the last rev.");
+        assert!(sender.submit(b"late".to_vec()).is_err(), "Submitting after shutdown should fail.");
+        verify(b"service", &test_profile().clock, &records);
+    }
+
+    #[test]
+    fn backpressure_and_flush_on_shutdown() {
+        let config: ServiceConfig = ServiceConfig {
+            channel_capacity: 2,
+            batch_size: 1,
+            ..ServiceConfig::default()
+        };
+        let service: PoHService<Hasher, FastForwardClock> = PoHService::spawn(generator(b"backpressure", &test_profile(), FastForwardClock::new()), &config).unwrap();
+        let sender: EventSender = service.sender();
+
+        // Nothing reads the records, so the generator stalls and the event queue fills up.
+        let mut accepted: Vec<Vec<u8>> = Vec::new();
+        let mut full: bool = false;
+        for i in 0..10_000u32 {
+            match sender.try_submit(i.to_le_bytes().to_vec()) {
+                Ok(()) => accepted.push(i.to_le_bytes().to_vec()),
+                Err(TrySendError::Full(_)) => {
+                    full = true;
+                    break;
+                }
+                Err(TrySendError::Disconnected(_)) => panic!("Service stopped unexpectedly."),
+            }
+            std::thread::yield_now();
+        }
+        assert!(full, "A full queue should push back on submitters.");
+
+        let (_, records): (PoH<Hasher, FastForwardClock>, Vec<Record>) = service.shutdown().unwrap();
+        let events: Vec<Vec<u8>> = records.iter().flat_map(|record| record.events.clone()).collect();
+        assert_eq!(events, accepted, "Shutdown should flush every accepted event.");
+        verify(b"backpressure", &test_profile().clock, &records);
+    }
+
+    #[test]
+    fn stops_while_producers_keep_submitting() {
+        let service: PoHService<Hasher, FastForwardClock> =
+            PoHService::spawn(generator(b"busy", &test_profile(), FastForwardClock::new()), &ServiceConfig::default()).unwrap();
+        let sender: EventSender = service.sender();
+        let producer: JoinHandle<u32> = thread::spawn(move || {
+            let mut sent: u32 = 0;
+            while sender.submit(sent.to_le_bytes().to_vec()).is_ok() {
+                sent = sent.saturating_add(1);
+            }
+            return sent;
+        });
+
+        let mut records: Vec<Record> = service.records().recv().unwrap();
+        let (poh, rest): (PoH<Hasher, FastForwardClock>, Vec<Record>) = service.shutdown().unwrap();
+        records.extend(rest);
+        let sent: u32 = producer.join().unwrap();
+
+        let events: Vec<Vec<u8>> = records.iter().flat_map(|record| record.events.clone()).collect();
+        let expected: Vec<Vec<u8>> = (0..events.len() as u32).map(|i| i.to_le_bytes().to_vec()).collect();
+        assert_eq!(events, expected, "Recorded events should be the first ones submitted, in order.");
+        assert!(events.len() as u32 <= sent, "Only submitted events should be recorded.");
+        assert_eq!(poh.rev_hashes, 0, "The service should still stop on a rev boundary.");
+        verify(b"busy", &test_profile().clock, &records);
+    }
+
+    #[test]
+    fn stops_when_dropped() {
+        let service: PoHService<Hasher, FastForwardClock> =
+            PoHService::spawn(generator(b"drop", &test_profile(), FastForwardClock::new()), &ServiceConfig::default()).unwrap();
+        let sender: EventSender = service.sender();
+        sender.submit(b"queued".to_vec()).unwrap();
+
+        drop(service);
+        assert!(sender.submit(b"late".to_vec()).is_err(), "Dropping the service should stop the generator.");
+    }
+}
@@ -0,0 +1,187 @@
+#[cfg(test)]
+mod snapshot {
+    use poh::types::{LedgerError, PoH, Record, Snapshot, SnapshotError};
+
+    use lib::{
+        clock::{Clock, FastForwardClock, ManualClock},
+        hash::{Algorithm, ChainHasher, Hasher},
+        profile::{Profile, ProfileConfig},
+    };
+
+    const SEED: &[u8] = b"snapshot";
+    const UNIX_US: u64 = 1_700_000_000_000_000;
+
+    fn profile() -> ProfileConfig {
+        return ProfileConfig::named(Profile::Test).unwrap();
+    }
+
+    // Records without their timestamps, which are relative to each generator's own clock.
+    fn positions(records: &[Record]) -> Vec<Record> {
+        return records
+            .iter()
+            .map(|record| Record {
+                timestamp_ms: 0,
+                ..record.clone()
+            })
+            .collect();
+    }
+
+    fn generate<H: ChainHasher, C: Clock>(poh: &mut PoH<H, C>) -> Vec<Record> {
+        let mut records: Vec<Record> = vec![poh.insert_events(&[b"first"]), poh.next_rev()];
+        records.extend((0..20).map(|_| poh.next_rev()));
+        return records;
+    }
+
+    #[test]
+    fn restores_the_chain_position() {
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(SEED, Hasher::default(), &profile(), FastForwardClock::new()).unwrap();
+        (0..10).for_each(|_| drop(poh.next_rev()));
+        let _: Record = poh.insert_events(&[b"mid", b"rev"]);
+
+        let snapshot: Snapshot = poh.snapshot_at(UNIX_US);
+        let mut restored: PoH<Hasher, FastForwardClock> = PoH::restore_at(&snapshot, Hasher::default(), FastForwardClock::new(), UNIX_US).unwrap();
+        assert_eq!(restored.ledger_meta(), poh.ledger_meta(), "The profile should be restored.");
+        assert_eq!(restored.rev_hashes, poh.rev_hashes, "Hashes spent mid-rev should be restored.");
+
+        assert_eq!(
+            positions(&generate(&mut restored)),
+            positions(&generate(&mut poh)),
+            "A restored generator should continue the same chain."
+        );
+    }
+
+    #[test]
+    fn rejects_a_different_hasher() {
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(SEED, Hasher::new(Algorithm::BLAKE3), &profile(), FastForwardClock::new()).unwrap();
+        (0..3).for_each(|_| drop(poh.next_rev()));
+        let snapshot: Snapshot = poh.snapshot_at(UNIX_US);
+        assert_eq!(snapshot.meta.algorithm, Algorithm::BLAKE3, "The snapshot should record the algorithm.");
+
+        assert_eq!(
+            PoH::restore_at(&snapshot, Hasher::new(Algorithm::SHA256), FastForwardClock::new(), UNIX_US).err(),
+            Some(SnapshotError::Hasher(LedgerError::AlgorithmMismatch {
+                expected: Algorithm::SHA256,
+                actual: Algorithm::BLAKE3,
+            })),
+            "Restoring with another algorithm should be rejected."
+        );
+        assert!(
+            PoH::restore_at(&snapshot, Hasher::new(Algorithm::BLAKE3), FastForwardClock::new(), UNIX_US).is_ok(),
+            "Restoring with the recorded algorithm should succeed."
+        );
+    }
+
+    #[test]
+    fn keeps_the_original_schedule() {
+        let config: ProfileConfig = profile();
+        let us_per_rev: u64 = config.clock.us_per_rev;
+        let clock: ManualClock = ManualClock::new();
+        let mut poh: PoH<Hasher, ManualClock> = PoH::with_clock(SEED, Hasher::default(), &config, clock.clone()).unwrap();
+        for _ in 0..5 {
+            clock.advance(us_per_rev);
+            let _: Record = poh.next_rev();
+        }
+        // Halfway through the sixth rev, which is due one rev after the snapshot's clock time.
+        clock.advance(us_per_rev / 2);
+        let snapshot: Snapshot = poh.snapshot_at(UNIX_US);
+        assert_eq!(
+            snapshot.next_rev_unix_us,
+            UNIX_US.saturating_add(us_per_rev / 2),
+            "The rev deadline should be anchored to wall time."
+        );
+
+        // Another process, whose clock started at a different time.
+        let other: ManualClock = ManualClock::new();
+        other.set(7_000_000);
+        let deadline_us: u64 = 7_000_000_u64.saturating_add(us_per_rev / 2);
+        let restored: PoH<Hasher, ManualClock> = PoH::restore_at(&snapshot, Hasher::default(), other.clone(), UNIX_US).unwrap();
+        assert_eq!(restored.next_rev_target_us, deadline_us, "The deadline should carry over to the new clock.");
+
+        // Restored three revs later, the missed revs are due already.
+        let late: PoH<Hasher, ManualClock> = PoH::restore_at(&snapshot, Hasher::default(), other.clone(), UNIX_US.saturating_add(us_per_rev.saturating_mul(3))).unwrap();
+        assert_eq!(
+            late.next_rev_target_us,
+            deadline_us.saturating_sub(us_per_rev.saturating_mul(3)),
+            "Missed revs should stay on schedule."
+        );
+
+        // A deadline from before the new clock started is clamped to its start.
+        other.set(0);
+        let clamped: PoH<Hasher, ManualClock> = PoH::restore_at(&snapshot, Hasher::default(), other, UNIX_US.saturating_add(us_per_rev.saturating_mul(3))).unwrap();
+        assert_eq!(clamped.next_rev_target_us, 0, "The deadline should not underflow the clock.");
+    }
+
+    #[test]
+    fn continues_unix_timestamps() {
+        let config: ProfileConfig = profile();
+        let clock: ManualClock = ManualClock::new();
+        let mut poh: PoH<Hasher, ManualClock> = PoH::with_clock(SEED, Hasher::default(), &config, clock.clone()).unwrap().at_unix_time(UNIX_US);
+        clock.advance(config.clock.us_per_rev);
+        let last: Record = poh.next_rev();
+        let snapshot: Snapshot = poh.snapshot_at(UNIX_US.saturating_add(clock.now_us()));
+
+        // Restored a second later, on a clock that started elsewhere.
+        let other: ManualClock = ManualClock::new();
+        other.set(3_000_000);
+        let later_us: u64 = UNIX_US.saturating_add(clock.now_us()).saturating_add(1_000_000);
+        let mut restored: PoH<Hasher, ManualClock> = PoH::restore_at(&snapshot, Hasher::default(), other, later_us).unwrap();
+        assert_eq!(restored.ledger_meta().genesis_unix_us, Some(UNIX_US), "The genesis should be restored.");
+        assert_eq!(
+            restored.next_rev().timestamp_ms,
+            last.timestamp_ms.saturating_add(1_000),
+            "Timestamps should stay on UNIX time."
+        );
+    }
+
+    #[test]
+    fn rejects_inconsistent_counters() {
+        let poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(SEED, Hasher::default(), &profile(), FastForwardClock::new()).unwrap();
+        let snapshot: Snapshot = poh.snapshot_at(UNIX_US);
+
+        let shifted: Snapshot = Snapshot {
+            rev_count: snapshot.rev_count.saturating_add(1),
+            ..snapshot
+        };
+        assert!(
+            PoH::restore_at(&shifted, Hasher::default(), FastForwardClock::new(), UNIX_US).is_ok(),
+            "Consistent counters should restore."
+        );
+
+        let forged: Snapshot = Snapshot {
+            phase_count: snapshot.phase_count.saturating_add(1),
+            ..snapshot
+        };
+        assert_eq!(
+            PoH::restore_at(&forged, Hasher::default(), FastForwardClock::new(), UNIX_US).err(),
+            Some(SnapshotError::Counters),
+            "A forged phase should be rejected."
+        );
+
+        let spent: Snapshot = Snapshot {
+            rev_hashes: profile().clock.hashes_per_rev,
+            ..snapshot
+        };
+        assert_eq!(
+            PoH::restore_at(&spent, Hasher::default(), FastForwardClock::new(), UNIX_US).err(),
+            Some(SnapshotError::Counters),
+            "A fully spent rev should be rejected."
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn round_trips_through_json() {
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(SEED, Hasher::default(), &profile(), FastForwardClock::new()).unwrap();
+        let _: Vec<Record> = generate(&mut poh);
+        let json: String = serde_json::to_string(&poh.snapshot()).unwrap();
+        let snapshot: Snapshot = serde_json::from_str(&json).unwrap();
+
+        let restored: PoH<Hasher, ManualClock> = PoH::restore(&snapshot, Hasher::default(), ManualClock::new()).unwrap();
+        assert_eq!(restored.current_hash, poh.current_hash, "The hash should survive serialization.");
+        assert_eq!(restored.rev_count, poh.rev_count, "The rev counter should survive serialization.");
+        assert!(
+            restored.next_rev_target_us <= poh.next_rev_target_us.saturating_sub(poh.clock.now_us()),
+            "The next rev should be due no later than it was when the snapshot was taken."
+        );
+    }
+}
@@ -0,0 +1,297 @@
+#[cfg(test)]
+mod ledger_store {
+    use std::{
+        fs::{self, OpenOptions},
+        io::Write,
+        path::PathBuf,
+        process,
+    };
+
+    use poh::types::{Anchor, FsyncPolicy, LedgerMeta, LedgerStore, PoH, Record, ResumeError, StoreConfig};
+
+    use lib::{
+        clock::FastForwardClock,
+        hash::{Hash, Hasher},
+        profile::{Profile, ProfileConfig},
+    };
+
+    const SEED: &[u8] = b"ledger store";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir: PathBuf = std::env::temp_dir().join(format!("poh-store-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        return dir;
+    }
+
+    fn generator() -> PoH<Hasher, FastForwardClock> {
+        return PoH::with_clock(SEED, Hasher::default(), &ProfileConfig::named(Profile::Test).unwrap(), FastForwardClock::new()).unwrap();
+    }
+
+    // Ticks, with a couple of mid-rev event entries every fifth rev.
+    fn generate(poh: &mut PoH<Hasher, FastForwardClock>, revs: u64) -> Vec<Record> {
+        let mut records: Vec<Record> = Vec::new();
+        for _ in 0..revs {
+            if poh.rev_count % 5 == 2 {
+                records.push(poh.insert_events(&[&poh.rev_count.to_le_bytes()]));
+                records.push(poh.insert_events(&[b"second"]));
+            }
+            records.push(poh.next_rev());
+        }
+        return records;
+    }
+
+    fn config() -> StoreConfig {
+        return StoreConfig {
+            segment_revs: 8,
+            fsync: FsyncPolicy::Always,
+        };
+    }
+
+    fn hashes(records: &[Record]) -> Vec<String> {
+        return records.iter().map(|record| record.hash.to_hex()).collect();
+    }
+
+    fn verify(meta: &LedgerMeta, records: &[Record]) {
+        let hasher: Hasher = meta.hasher();
+        assert!(
+            PoH::verify_anchored(&hasher, &meta.profile.clock, &Anchor::genesis(&hasher, SEED), records).is_ok(),
+            "Stored records should verify from the seed."
+        );
+    }
+
+    #[test]
+    fn resumes_the_same_chain() {
+        let dir: PathBuf = temp_dir("resume");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let meta: LedgerMeta = poh.ledger_meta();
+        let mut expected: Vec<Record> = generate(&mut poh, 30);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, meta, SEED, config()).unwrap();
+        for batch in expected.chunks(7) {
+            store.append(batch).unwrap();
+        }
+        drop(store);
+        assert!(
+            LedgerStore::create(&dir, meta, SEED, config()).is_err(),
+            "An existing ledger should not be overwritten."
+        );
+
+        let mut store: LedgerStore = LedgerStore::open(&dir, config()).unwrap();
+        assert_eq!(hashes(&store.records().unwrap()), hashes(&expected), "Reopened ledger should hold every record.");
+        assert_eq!(store.next_rev(), 30, "Reopened ledger should continue after its last rev.");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1 + 2 * 4, "Segments should rotate every 8 revs.");
+
+        let mut resumed: PoH<Hasher, FastForwardClock> = store.resume(FastForwardClock::new()).unwrap();
+        let more: Vec<Record> = generate(&mut resumed, 10);
+        store.append(&more).unwrap();
+        expected.extend(generate(&mut poh, 10));
+
+        let stored: Vec<Record> = store.records().unwrap();
+        assert_eq!(hashes(&stored), hashes(&expected), "Resumed generation should continue the same chain.");
+        verify(&meta, &stored);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resumes_on_unix_time() {
+        const UNIX_US: u64 = 1_700_000_000_000_000;
+        let dir: PathBuf = temp_dir("unix");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator().at_unix_time(UNIX_US);
+        let meta: LedgerMeta = poh.ledger_meta();
+        let records: Vec<Record> = generate(&mut poh, 5);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, meta, SEED, config()).unwrap();
+        store.append(&records).unwrap();
+        assert_eq!(store.header().meta.genesis_unix_us, Some(UNIX_US), "The header should keep the UNIX genesis.");
+
+        let mut resumed: PoH<Hasher, FastForwardClock> = store.resume(FastForwardClock::new()).unwrap();
+        assert_eq!(resumed.ledger_meta(), meta, "A resumed generator should keep the ledger's genesis.");
+        let tick: Record = resumed.next_rev();
+        assert!(tick.timestamp_ms > records[4].timestamp_ms, "A resumed generator should keep stamping UNIX time.");
+
+        let relative_dir: PathBuf = temp_dir("relative");
+        let relative: LedgerStore = LedgerStore::create(&relative_dir, generator().ledger_meta(), SEED, config()).unwrap();
+        let resumed: PoH<Hasher, FastForwardClock> = relative.resume(FastForwardClock::new()).unwrap();
+        assert_eq!(resumed.epoch_unix_us, None, "A ledger without a UNIX genesis should stay on generator time.");
+        drop(relative);
+        let _ = fs::remove_dir_all(&dir);
+        let _ = fs::remove_dir_all(&relative_dir);
+    }
+
+    #[test]
+    fn truncates_torn_tail() {
+        let dir: PathBuf = temp_dir("torn");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let meta: LedgerMeta = poh.ledger_meta();
+        let records: Vec<Record> = generate(&mut poh, 12);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, meta, SEED, config()).unwrap();
+        store.append(&records).unwrap();
+        drop(store);
+
+        // Half of the next record, as if the process died mid-write.
+        let next: String = serde_json::to_string(&poh.next_rev()).unwrap();
+        let tail: PathBuf = dir.join(format!("{:020}.log", 8));
+        let length: u64 = fs::metadata(&tail).unwrap().len();
+        OpenOptions::new()
+            .append(true)
+            .open(&tail)
+            .unwrap()
+            .write_all(&next.as_bytes()[..next.len() / 2])
+            .unwrap();
+
+        let mut store: LedgerStore = LedgerStore::open(&dir, config()).unwrap();
+        assert_eq!(fs::metadata(&tail).unwrap().len(), length, "The torn write should be cut off.");
+        assert_eq!(hashes(&store.records().unwrap()), hashes(&records), "Durable records should survive.");
+
+        let mut resumed: PoH<Hasher, FastForwardClock> = store.resume(FastForwardClock::new()).unwrap();
+        store.append(&generate(&mut resumed, 3)).unwrap();
+        verify(&meta, &store.records().unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn resumes_mid_rev() {
+        let dir: PathBuf = temp_dir("mid-rev");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let meta: LedgerMeta = poh.ledger_meta();
+        let mut records: Vec<Record> = generate(&mut poh, 3);
+        records.push(poh.insert_events(&[b"pending"]));
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, meta, SEED, config()).unwrap();
+        store.append(&records).unwrap();
+        drop(store);
+
+        let mut store: LedgerStore = LedgerStore::open(&dir, config()).unwrap();
+        let mut resumed: PoH<Hasher, FastForwardClock> = store.resume(FastForwardClock::new()).unwrap();
+        assert_eq!(resumed.rev_count, poh.rev_count, "Resume should stay in the unfinished rev.");
+        assert_eq!(resumed.rev_hashes, poh.rev_hashes, "Resume should count the hashes already spent in the rev.");
+
+        let tick: Record = resumed.next_rev();
+        assert_eq!(tick.hash, poh.next_rev().hash, "The rev should end as if never interrupted.");
+        store.append(&[tick]).unwrap();
+        verify(&meta, &store.records().unwrap());
+
+        let anchor: Anchor = Anchor {
+            hash: poh.current_hash,
+            next_rev: poh.rev_count,
+        };
+        let hashes_per_rev: u64 = meta.profile.clock.hashes_per_rev;
+        assert_eq!(
+            PoH::resume(Hasher::default(), &meta.profile, FastForwardClock::new(), &anchor, hashes_per_rev).err(),
+            Some(ResumeError::RevHashes {
+                rev_hashes: hashes_per_rev,
+                hashes_per_rev
+            }),
+            "A position past the end of its rev should be rejected."
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reads_from_rev_through_index() {
+        let dir: PathBuf = temp_dir("index");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let records: Vec<Record> = generate(&mut poh, 20);
+
+        let mut store: LedgerStore = LedgerStore::create(
+            &dir,
+            poh.ledger_meta(),
+            SEED,
+            StoreConfig {
+                fsync: FsyncPolicy::Manual,
+                ..config()
+            },
+        )
+        .unwrap();
+        store.append(&records).unwrap();
+
+        for rev in [0, 7, 8, 12, 19] {
+            let first: usize = records.iter().position(|record| record.rev_index == rev).unwrap();
+            assert_eq!(
+                hashes(&store.read_from(rev).unwrap()),
+                hashes(&records[first..]),
+                "Reading from rev {} should start at its first entry.",
+                rev
+            );
+        }
+        assert!(store.read_from(20).unwrap().is_empty(), "Reading past the end should return nothing.");
+        store.sync().unwrap();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_records_that_do_not_follow() {
+        let dir: PathBuf = temp_dir("follow");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let records: Vec<Record> = generate(&mut poh, 4);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, poh.ledger_meta(), SEED, config()).unwrap();
+        assert!(store.append(&records[1..]).is_err(), "A ledger should start at rev 0.");
+        store.append(&records).unwrap();
+        assert!(store.append(&records[3..]).is_err(), "Records should not be appended twice.");
+
+        let mut forged: Record = poh.next_rev();
+        forged.hash = Hash::default();
+        assert!(store.append(&[forged]).is_err(), "A record that breaks the hash chain should be rejected.");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn rejects_a_batch_as_a_whole() {
+        let dir: PathBuf = temp_dir("batch");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let records: Vec<Record> = generate(&mut poh, 12);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, poh.ledger_meta(), SEED, config()).unwrap();
+        store.append(&records[..4]).unwrap();
+        let mut batch: Vec<Record> = records[4..].to_vec();
+        let last: usize = batch.len().saturating_sub(1);
+        batch[last].hash = Hash::default();
+        assert!(store.append(&batch).is_err(), "A batch ending in a forged record should be rejected.");
+        assert_eq!(
+            hashes(&store.records().unwrap()),
+            hashes(&records[..4]),
+            "Nothing of a rejected batch should be written."
+        );
+        assert_eq!(
+            hashes(&LedgerStore::open(&dir, config()).unwrap().records().unwrap()),
+            hashes(&records[..4]),
+            "Nothing of a rejected batch should be found on reopening."
+        );
+
+        store.append(&records[4..]).unwrap();
+        assert_eq!(hashes(&store.records().unwrap()), hashes(&records), "The valid batch should still append.");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cuts_tail_at_broken_chain() {
+        let dir: PathBuf = temp_dir("chain");
+        let mut poh: PoH<Hasher, FastForwardClock> = generator();
+        let meta: LedgerMeta = poh.ledger_meta();
+        let records: Vec<Record> = generate(&mut poh, 12);
+
+        let mut store: LedgerStore = LedgerStore::create(&dir, meta, SEED, config()).unwrap();
+        store.append(&records).unwrap();
+        drop(store);
+
+        // A whole line that parses and keeps its indices, but no longer continues the chain.
+        let tail: PathBuf = dir.join(format!("{:020}.log", 8));
+        let mut lines: Vec<String> = fs::read_to_string(&tail).unwrap().lines().map(String::from).collect();
+        let mut corrupt: Record = serde_json::from_str(&lines[2]).unwrap();
+        corrupt.hash = Hash::default();
+        lines[2] = serde_json::to_string(&corrupt).unwrap();
+        fs::write(&tail, lines.join("\n") + "\n").unwrap();
+
+        let mut store: LedgerStore = LedgerStore::open(&dir, config()).unwrap();
+        let kept: Vec<Record> = store.records().unwrap();
+        assert_eq!(kept.len(), records.len() - lines.len() + 2, "The tail should be cut at the first broken record.");
+        assert_eq!(hashes(&kept), hashes(&records[..kept.len()]), "Records before it should survive.");
+
+        let mut resumed: PoH<Hasher, FastForwardClock> = store.resume(FastForwardClock::new()).unwrap();
+        store.append(&generate(&mut resumed, 3)).unwrap();
+        verify(&meta, &store.records().unwrap());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,559 @@
+#[cfg(test)]
+mod poh_verification {
+    use poh::types::{
+        Anchor, DriftPolicy, LedgerMeta, PoH, Record, SampleConfig, SampleReport, VerificationError, VerificationErrorKind, VerificationReport, VerifyMode,
+    };
+
+    use lib::{
+        clock::{Clock, FastForwardClock, ManualClock},
+        hash::{Algorithm, ChainHasher, Hash, Hasher},
+        metronome::{ClockConfig, DEFAULT_HASHES_PER_REV, DEFAULT_PHASES_PER_CYCLE, DEFAULT_REVS_PER_PHASE},
+        profile::{Profile, ProfileConfig},
+    };
+
+    // Builds a ledger with the hashing primitives only, so it works without the std generator.
+    fn ledger<H: ChainHasher>(hasher: &H, seed: &[u8], count: u64) -> Vec<Record> {
+        let mut current_hash: Hash = hasher.genesis_hash(seed);
+        let mut records: Vec<Record> = Vec::new();
+
+        for rev_index in 0..count {
+            let events: Vec<Vec<u8>> = if rev_index % 4 == 1 { vec![rev_index.to_le_bytes().to_vec()] } else { Vec::new() };
+
+            for data in &events {
+                current_hash = hasher.embed_data(&current_hash, data);
+            }
+            current_hash = hasher.extend_hash_chain(&current_hash, DEFAULT_HASHES_PER_REV);
+            records.push(Record {
+                hash: current_hash,
+                rev_index,
+                phase_index: rev_index / DEFAULT_REVS_PER_PHASE,
+                cycle_index: rev_index / (DEFAULT_REVS_PER_PHASE * DEFAULT_PHASES_PER_CYCLE),
+                timestamp_ms: 0,
+                num_hashes: None,
+                events,
+            });
+        }
+        return records;
+    }
+
+    #[test]
+    fn verifies_ledger_without_generator() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"no_std", 16);
+
+        assert!(PoH::verify_records(&records).is_ok(), "Ledger built from primitives should verify.");
+        assert!(PoH::verify_records(&[]).is_err(), "Empty ledger should not verify.");
+    }
+
+    #[test]
+    fn rejects_tampered_ledger() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"no_std", 8);
+
+        let mut corrupted: Vec<Record> = records.clone();
+        corrupted[3].hash.as_mut()[0] ^= 1;
+        assert!(PoH::verify_records(&corrupted).is_err(), "Corrupted hash should be rejected.");
+
+        corrupted = records.clone();
+        corrupted[5].events = vec![b"forged".to_vec()];
+        assert!(PoH::verify_records(&corrupted).is_err(), "Forged event should be rejected.");
+
+        corrupted = records;
+        corrupted[6].rev_index += 1;
+        assert!(PoH::verify_records(&corrupted).is_err(), "Rev gap should be rejected.");
+    }
+
+    #[test]
+    fn verifies_with_selected_backend() {
+        let hasher: Hasher = Hasher::new(Algorithm::BLAKE3);
+        let records: Vec<Record> = ledger(&hasher, b"no_std", 8);
+
+        assert!(PoH::verify_records_with(&hasher, &records).is_ok(), "BLAKE3 ledger should verify with BLAKE3.");
+        assert!(PoH::verify_records(&records).is_err(), "BLAKE3 ledger should not verify with SHA-256.");
+    }
+
+    #[test]
+    fn sampled_verification_is_reproducible() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"sampled", 41);
+        let config: SampleConfig = SampleConfig {
+            samples: 10,
+            seed: 7,
+            forged_fraction: 0.1,
+            verify_events: true,
+            ..SampleConfig::default()
+        };
+        let report: SampleReport = PoH::sample_records(&records, config).report();
+
+        assert!(report.valid, "Valid ledger should pass sampling.");
+        assert_eq!(report.segments, 40, "Every adjacent pair should be a segment.");
+        // 10 event segments are always checked, plus 10 of the 30 tick segments.
+        assert_eq!(report.verified_segments, 20, "Events and samples should be re-hashed.");
+        // Missing 3 forged ticks out of 30 with 10 draws: C(27, 10) / C(30, 10) = 6840 / 24360.
+        assert!(
+            (report.confidence - (1.0 - 6840.0 / 24360.0)).abs() < 1e-9,
+            "Confidence should follow sampling without replacement."
+        );
+
+        // One forged tick segment is found by some seeds and missed by others, the same way every run.
+        let mut forged: Vec<Record> = records.clone();
+        forged[3].hash.as_mut()[0] ^= 1;
+        let ticks_only: SampleConfig = SampleConfig { verify_events: false, ..config };
+        let outcomes: Vec<bool> = (0..6)
+            .map(|seed| PoH::sample_records(&forged, SampleConfig { seed, ..ticks_only }).report().valid)
+            .collect();
+        let repeated: Vec<bool> = (0..6)
+            .map(|seed| PoH::sample_records(&forged, SampleConfig { seed, ..ticks_only }).report().valid)
+            .collect();
+
+        assert_eq!(outcomes, repeated, "The same seed should sample the same segments.");
+        assert!(
+            outcomes.contains(&true) && outcomes.contains(&false),
+            "Different seeds should sample different segments."
+        );
+    }
+
+    #[test]
+    fn sampling_until_target_confidence() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"sampled", 41);
+        let config: SampleConfig = SampleConfig {
+            samples: 0,
+            ..SampleConfig::default()
+        };
+        let mut verifier = PoH::sample_records(&records, config);
+        let initial: SampleReport = verifier.report();
+        let reached: SampleReport = verifier.sample_until(0.5, 4);
+
+        assert_eq!(initial.confidence, 0.0, "No samples should give no confidence.");
+        assert!(reached.confidence >= 0.5, "Sampling should continue until the target is met.");
+        assert!(reached.verified_segments < reached.segments, "Sampling should stop once the target is met.");
+        assert_eq!(verifier.sample_until(1.0, 4).confidence, 1.0, "Sampling every segment should give full confidence.");
+
+        // Exhaustive sampling always finds a forged tick.
+        let mut forged: Vec<Record> = records;
+        forged[3].hash.as_mut()[0] ^= 1;
+        let report: SampleReport = PoH::sample_records(&forged, config).sample_until(1.0, 4);
+
+        assert!(!report.valid, "Exhaustive sampling should find the forged segment.");
+        assert!(
+            matches!(report.failed_segment, Some(2) | Some(3)),
+            "Failure should point at a segment touching the forged record."
+        );
+    }
+
+    #[test]
+    fn sampling_checks_indices_and_events_on_every_record() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"sampled", 9);
+        let config: SampleConfig = SampleConfig {
+            samples: 0,
+            ..SampleConfig::default()
+        };
+
+        let mut gap: Vec<Record> = records.clone();
+        gap[6].rev_index += 1;
+        let report: SampleReport = PoH::sample_records(&gap, config).report();
+        assert_eq!(report.failed_segment, Some(5), "Index gaps should be found without sampling.");
+        assert_eq!(report.verified_segments, 0, "Index checks should not need re-hashing.");
+
+        let mut forged_event: Vec<Record> = records;
+        forged_event[5].events = vec![b"forged".to_vec()];
+        let report: SampleReport = PoH::sample_records(&forged_event, config).report();
+        assert_eq!(report.failed_segment, Some(4), "Event segments should always be re-hashed.");
+        assert!(!PoH::sample_records(&[], config).report().valid, "Empty ledger should not verify.");
+    }
+
+    #[test]
+    fn single_record_has_no_confidence() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"sampled", 1);
+        let mut verifier = PoH::sample_records(&records, SampleConfig::default());
+        let report: SampleReport = verifier.sample_until(0.99, 4);
+
+        assert_eq!(report.segments, 0, "A single record should have no segments.");
+        assert_eq!(report.verified_segments, 0, "No segment should be reported as checked.");
+        assert_eq!(report.confidence, 0.0, "Nothing checked should back no confidence.");
+    }
+
+    #[test]
+    fn reports_failing_record_and_kind() {
+        let records: Vec<Record> = ledger(&Hasher::default(), b"errors", 8);
+
+        let mut corrupted: Vec<Record> = records.clone();
+        corrupted[3].hash.as_mut()[0] ^= 1;
+        assert_eq!(
+            PoH::verify_records(&corrupted),
+            Err(VerificationError {
+                index: Some(3),
+                kind: VerificationErrorKind::HashMismatch {
+                    expected: records[3].hash,
+                    actual: corrupted[3].hash,
+                },
+            }),
+            "Hash mismatches should name the record and both hashes."
+        );
+
+        corrupted = records.clone();
+        corrupted[6].rev_index += 1;
+        assert_eq!(
+            PoH::verify_records(&corrupted).unwrap_err().kind,
+            VerificationErrorKind::RevGap { expected: 6, actual: 7 },
+            "Rev gaps should report the expected index."
+        );
+
+        corrupted = records.clone();
+        corrupted[2].phase_index = 9;
+        assert_eq!(
+            PoH::verify_records(&corrupted),
+            Err(VerificationError {
+                index: Some(2),
+                kind: VerificationErrorKind::PhaseIndex { expected: 0, actual: 9 },
+            }),
+            "Wrong phase indices should be reported."
+        );
+
+        corrupted = records;
+        corrupted[4].cycle_index = 1;
+        assert_eq!(
+            PoH::verify_records(&corrupted).unwrap_err().kind,
+            VerificationErrorKind::CycleIndex { expected: 0, actual: 1 },
+            "Wrong cycle indices should be reported."
+        );
+
+        let empty: VerificationError = PoH::verify_records(&[]).unwrap_err();
+        assert_eq!(empty.kind, VerificationErrorKind::EmptyInput, "Empty input should have its own kind.");
+        assert_eq!(empty.index, None, "Empty input concerns no record.");
+    }
+
+    #[test]
+    fn collects_every_failure() {
+        let hasher: Hasher = Hasher::default();
+        let config: ClockConfig = ClockConfig::default();
+        let mut records: Vec<Record> = ledger(&hasher, b"collect", 8);
+        records[2].phase_index = 9;
+        records[5].hash.as_mut()[0] ^= 1;
+
+        let first: VerificationReport = PoH::records_report(&hasher, &config, &records, VerifyMode::StopAtFirst);
+        assert_eq!(first.errors.len(), 1, "The default mode should stop at the first failure.");
+
+        let all: VerificationReport = PoH::records_report(&hasher, &config, &records, VerifyMode::CollectAll);
+        let indices: Vec<Option<usize>> = all.errors.iter().map(|error| error.index).collect();
+        // The forged hash breaks its own segment and the next one.
+        assert_eq!(indices, vec![Some(2), Some(5), Some(6)], "Every failure should be collected in record order.");
+        assert!(!all.is_valid(), "Report with failures should be invalid.");
+        assert_eq!(all.records, 8, "Report should count the records checked.");
+    }
+
+    #[test]
+    fn reports_timestamp_drift() {
+        let config: ClockConfig = ClockConfig::default();
+        let mut records: Vec<Record> = ledger(&Hasher::default(), b"timestamps", 8);
+        for (i, record) in records.iter_mut().enumerate() {
+            record.timestamp_ms = 1_000 + i as u64 * 6;
+        }
+        assert!(PoH::verify_timestamps(&records).is_ok(), "Paced timestamps should verify.");
+
+        records[3].timestamp_ms += 20;
+        records[6].timestamp_ms = 0;
+        let report: VerificationReport = PoH::timestamps_report(&config, &records, VerifyMode::CollectAll);
+        assert_eq!(
+            report.errors,
+            vec![
+                VerificationError {
+                    index: Some(3),
+                    kind: VerificationErrorKind::TimestampDrift {
+                        expected_ms: 1_018,
+                        actual_ms: 1_038,
+                        allowed_ms: 8,
+                    },
+                },
+                VerificationError {
+                    index: Some(6),
+                    kind: VerificationErrorKind::TimestampDrift {
+                        expected_ms: 1_037,
+                        actual_ms: 0,
+                        allowed_ms: 8,
+                    },
+                },
+            ],
+            "Drift should report expected and actual timestamps."
+        );
+        assert_eq!(
+            report,
+            PoH::drift_report(&config, None, DriftPolicy::default(), &records, VerifyMode::CollectAll),
+            "Timestamp checks should apply the default drift policy."
+        );
+    }
+
+    #[test]
+    fn anchors_first_record_to_genesis() {
+        let hasher: Hasher = Hasher::default();
+        let records: Vec<Record> = ledger(&hasher, b"genesis", 8);
+
+        assert!(PoH::verify_from_seed(b"genesis", &records).is_ok(), "Ledger should verify from its seed.");
+
+        let other: Vec<Record> = ledger(&hasher, b"other", 8);
+        assert!(PoH::verify_records(&other).is_ok(), "Unanchored verification trusts the first record.");
+        let error: VerificationError = PoH::verify_from_seed(b"genesis", &other).unwrap_err();
+        assert_eq!(error.index, Some(0), "A chain from another seed should fail at its first record.");
+        assert!(
+            matches!(error.kind, VerificationErrorKind::HashMismatch { .. }),
+            "The first hash should not follow the genesis hash."
+        );
+
+        let lone: &[Record] = &records[3..4];
+        assert!(PoH::verify_records(lone).is_ok(), "A lone record passes unanchored verification.");
+        assert_eq!(
+            PoH::verify_from_seed(b"genesis", lone).unwrap_err().kind,
+            VerificationErrorKind::RevGap { expected: 0, actual: 3 },
+            "A lone record should not verify from genesis."
+        );
+    }
+
+    #[test]
+    fn anchors_to_checkpoint() {
+        let hasher: Hasher = Hasher::default();
+        let config: ClockConfig = ClockConfig::default();
+        let records: Vec<Record> = ledger(&hasher, b"checkpoint", 12);
+        let checkpoint: Anchor = Anchor::checkpoint(records[4].hash, 4);
+
+        assert_eq!(checkpoint, Anchor::after(&records[4]), "A checkpoint is the position after its record.");
+        assert!(
+            PoH::verify_anchored(&hasher, &config, &checkpoint, &records[5..]).is_ok(),
+            "Ledger should verify from a mid-ledger checkpoint."
+        );
+        assert_eq!(
+            PoH::verify_anchored(&hasher, &config, &checkpoint, &records[6..]).unwrap_err().kind,
+            VerificationErrorKind::RevGap { expected: 5, actual: 6 },
+            "Records must start right after the checkpoint."
+        );
+
+        let wrong: Anchor = Anchor::checkpoint(records[3].hash, 4);
+        assert_eq!(
+            PoH::verify_anchored(&hasher, &config, &wrong, &records[5..]).unwrap_err().index,
+            Some(0),
+            "A wrong checkpoint hash should fail the first record."
+        );
+    }
+
+    #[test]
+    fn generates_with_manual_clock() {
+        let clock: ManualClock = ManualClock::new();
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let mut poh: PoH<Hasher, ManualClock> = PoH::with_clock(b"manual", Hasher::default(), &profile, clock.clone()).unwrap();
+
+        let first: Record = poh.next_rev();
+        clock.advance(5_000);
+        let second: Record = poh.insert_event(b"Manual event");
+        clock.set(7_999);
+        let third: Record = poh.next_rev();
+
+        let timestamps: Vec<u64> = [&first, &second, &third].iter().map(|record| record.timestamp_ms).collect();
+        assert_eq!(timestamps, vec![0, 5, 7], "Timestamps should come from the manual clock.");
+        assert!(
+            PoH::verify_anchored(
+                &Hasher::default(),
+                &profile.clock,
+                &Anchor::genesis(&Hasher::default(), b"manual"),
+                &[first, second, third]
+            )
+            .is_ok(),
+            "Records should verify regardless of the clock."
+        );
+    }
+
+    #[test]
+    fn fast_forward_is_deterministic() {
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let generate = || -> (Vec<Record>, LedgerMeta) {
+            let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"fast", Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+            let records: Vec<Record> = (0..2_000).map(|i| if i % 7 == 0 { poh.insert_event(&[i as u8]) } else { poh.next_rev() }).collect();
+            assert_eq!(poh.clock.now_us(), 2_000 * profile.clock.us_per_rev, "Virtual time should advance one rev per rev.");
+            return (records, poh.ledger_meta());
+        };
+        let (records, meta): (Vec<Record>, LedgerMeta) = generate();
+        let (repeated, _): (Vec<Record>, LedgerMeta) = generate();
+
+        assert!(PoH::verify_ledger(&meta, &profile, &records).is_ok(), "Fast-forwarded records should verify.");
+        assert!(
+            PoH::timestamps_report(&profile.clock, &records, VerifyMode::CollectAll).is_valid(),
+            "Fast-forwarded timestamps should be plausible."
+        );
+        assert!(
+            records.iter().zip(&repeated).all(|(a, b)| a.hash == b.hash && a.timestamp_ms == b.timestamp_ms),
+            "Runs should be identical, timestamps included."
+        );
+    }
+
+    #[test]
+    fn fast_forwards_unpaced_profiles() {
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        assert!(!profile.clock.pace, "The test preset should not pace revs.");
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"unpaced", Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+        let records: Vec<Record> = (0..50).map(|_| poh.next_rev()).collect();
+
+        assert!(
+            records.windows(2).all(|pair| pair[0].timestamp_ms < pair[1].timestamp_ms),
+            "Unpaced revs should still move virtual time forward."
+        );
+        assert!(
+            PoH::timestamps_report(&profile.clock, &records, VerifyMode::CollectAll).is_valid(),
+            "Unpaced timestamps should follow the schedule."
+        );
+    }
+
+    #[test]
+    fn checks_entry_hash_counts() {
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let config: ClockConfig = profile.clock;
+        let mut poh: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"counts", Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+        let records: Vec<Record> = vec![
+            poh.next_rev(),
+            poh.insert_events(&[b"a"]),
+            poh.insert_events(&[b"b", b"c"]),
+            poh.next_rev(),
+            poh.next_rev(),
+        ];
+        let anchor: Anchor = Anchor::genesis(&Hasher::default(), b"counts");
+        let report = |records: &[Record]| -> Vec<VerificationErrorKind> {
+            return PoH::anchored_report(&Hasher::default(), &config, &anchor, records, VerifyMode::CollectAll)
+                .errors
+                .into_iter()
+                .map(|error| error.kind)
+                .collect();
+        };
+        assert!(report(&records).is_empty(), "Generated entries should verify.");
+
+        let mut forged: Vec<Record> = records.clone();
+        forged[3].num_hashes = None;
+        assert_eq!(
+            report(&forged),
+            vec![VerificationErrorKind::HashCount {
+                remaining: config.hashes_per_rev - 2,
+                actual: config.hashes_per_rev,
+            }],
+            "A tick overrunning its rev should be rejected."
+        );
+
+        forged = records.clone();
+        forged[2].num_hashes = Some(0);
+        assert!(
+            matches!(report(&forged)[..], [VerificationErrorKind::HashCount { actual: 0, .. }, ..]),
+            "An entry without hashes should be rejected."
+        );
+
+        forged = records.clone();
+        forged[1].num_hashes = Some(2);
+        assert!(
+            matches!(report(&forged)[..], [VerificationErrorKind::HashMismatch { .. }, ..]),
+            "A forged hash count should not match the chain."
+        );
+
+        forged = records.clone();
+        forged[2].rev_index += 1;
+        assert!(
+            matches!(report(&forged)[0], VerificationErrorKind::RevGap { expected: 1, actual: 2 }),
+            "An entry should not start a new rev before the last one ended."
+        );
+
+        forged = records;
+        forged.remove(1);
+        assert!(
+            matches!(report(&forged)[..], [VerificationErrorKind::HashMismatch { .. }, ..]),
+            "A dropped entry should break the chain."
+        );
+    }
+
+    #[test]
+    fn stamps_unix_time() {
+        const UNIX_US: u64 = 1_700_000_000_000_000;
+        let profile: ProfileConfig = ProfileConfig::named(Profile::Test).unwrap();
+        let us_per_rev: u64 = profile.clock.us_per_rev;
+        // A node whose clock started at `started_us`, anchored to the same wall time as every other node.
+        let generate = |started_us: u64| -> (Vec<Record>, LedgerMeta) {
+            let clock: ManualClock = ManualClock::new();
+            clock.set(started_us);
+            let mut poh: PoH<Hasher, ManualClock> = PoH::with_clock(b"unix", Hasher::default(), &profile, clock.clone()).unwrap().at_unix_time(UNIX_US);
+            let records: Vec<Record> = (0..50)
+                .map(|_| {
+                    clock.advance(us_per_rev);
+                    return poh.next_rev();
+                })
+                .collect();
+            return (records, poh.ledger_meta());
+        };
+        let (records, meta): (Vec<Record>, LedgerMeta) = generate(0);
+        let (other, other_meta): (Vec<Record>, LedgerMeta) = generate(9_000_000);
+
+        assert_eq!(meta.genesis_unix_us, Some(UNIX_US), "Genesis should be the UNIX time the generator started at.");
+        assert_eq!(other_meta, meta, "Nodes started together should share a genesis, whatever their clocks read.");
+        assert_eq!(records[0].timestamp_ms, UNIX_US.saturating_add(us_per_rev) / 1_000, "Timestamps should be UNIX time.");
+        assert_eq!(other, records, "Nodes started together should agree on timestamps.");
+        assert!(
+            PoH::verify_ledger_timestamps(&meta, DriftPolicy::Absolute(0), &records).is_ok(),
+            "Timestamps should follow the genesis schedule."
+        );
+
+        let relative: PoH<Hasher, FastForwardClock> = PoH::with_clock(b"unix", Hasher::default(), &profile, FastForwardClock::new()).unwrap();
+        assert_eq!(relative.ledger_meta().genesis_unix_us, None, "A clock not anchored to wall time has no UNIX genesis.");
+    }
+
+    #[test]
+    fn applies_drift_policies() {
+        const GENESIS_US: u64 = 5_000_000;
+        let config: ClockConfig = ClockConfig::default();
+        let mut scheduled: Vec<Record> = ledger(&Hasher::default(), b"drift", 12);
+        for record in scheduled.iter_mut() {
+            record.timestamp_ms = GENESIS_US.saturating_add(record.rev_index.saturating_add(1).saturating_mul(config.us_per_rev)) / 1_000;
+        }
+        let report = |records: &[Record], policy: DriftPolicy| -> Vec<(Option<usize>, VerificationErrorKind)> {
+            let report: VerificationReport = PoH::drift_report(&config, Some(GENESIS_US), policy, records, VerifyMode::CollectAll);
+            return report.errors.iter().map(|error| (error.index, error.kind)).collect();
+        };
+        for policy in [DriftPolicy::Absolute(0), DriftPolicy::Cumulative(0), DriftPolicy::Monotonic] {
+            assert!(report(&scheduled, policy).is_empty(), "Scheduled timestamps should pass {:?}.", policy);
+        }
+
+        // A late record is off its own slot, and the gaps on either side are off by as much.
+        let mut late: Vec<Record> = scheduled.clone();
+        late[3].timestamp_ms += 3;
+        let drift = |index: usize, expected_ms: u64, allowed_ms: u64| -> (Option<usize>, VerificationErrorKind) {
+            return (
+                Some(index),
+                VerificationErrorKind::TimestampDrift {
+                    expected_ms,
+                    actual_ms: late[index].timestamp_ms,
+                    allowed_ms,
+                },
+            );
+        };
+        assert_eq!(
+            report(&late, DriftPolicy::Absolute(2)),
+            vec![drift(3, 5_025, 2)],
+            "Only the late record should be off schedule."
+        );
+        assert!(report(&late, DriftPolicy::Cumulative(6)).is_empty(), "Both gaps should fit the budget.");
+        assert_eq!(
+            report(&late, DriftPolicy::Cumulative(5)),
+            vec![drift(4, 5_034, 2)],
+            "The budget should run out on the second gap."
+        );
+        assert!(report(&late, DriftPolicy::Monotonic).is_empty(), "A late record still moves forward.");
+
+        // A lasting offset is charged once against the budget, but leaves every later record off schedule.
+        let mut offset: Vec<Record> = scheduled.clone();
+        offset.iter_mut().skip(6).for_each(|record| record.timestamp_ms += 5);
+        assert_eq!(report(&offset, DriftPolicy::Absolute(4)).len(), 6, "Every offset record should be off schedule.");
+        assert!(report(&offset, DriftPolicy::Cumulative(5)).is_empty(), "An offset should be charged once.");
+
+        let mut backwards: Vec<Record> = scheduled;
+        backwards[7].timestamp_ms = backwards[6].timestamp_ms - 1;
+        backwards.iter_mut().skip(9).for_each(|record| record.timestamp_ms += 1_000);
+        assert_eq!(
+            report(&backwards, DriftPolicy::Monotonic),
+            vec![(
+                Some(7),
+                VerificationErrorKind::TimestampBackwards {
+                    previous_ms: 5_043,
+                    actual_ms: 5_042,
+                }
+            )],
+            "Only time going backwards should fail a monotonic check."
+        );
+    }
+}